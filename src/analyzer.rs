@@ -0,0 +1,888 @@
+use super::error::{ErrorKind, ScorchError};
+use super::interpreter::{named_argument, Interpreter};
+use super::span::Spans;
+use super::standard_functions::{get_builtin_functions, Signature, StandardFunction};
+use super::typechecker::{overloaded_operator, TypeChecker};
+use scorch_parser::ast::*;
+use scorch_parser::lexer::TokenKind;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+// what the analyzer knows about a name without running anything.
+#[derive(Clone, Debug)]
+pub struct Symbol {
+    pub typename: String,
+    pub mutable: bool,
+    // known for declared functions, so calls to them can be checked.
+    pub signature: Option<Signature>,
+}
+
+// a pass over the ast that reports type mismatches, unknown names and arity errors
+// for a whole program without executing it. every expression evaluates to the name of its type,
+// `dynamic` whenever that can't be known before running.
+pub struct Analyzer {
+    scopes: Vec<HashMap<String, Symbol>>,
+    // fields & associated functions of each declared struct.
+    structs: HashMap<String, HashMap<String, Symbol>>,
+    // (field, declared type) of each struct in declaration order, for checking constructors.
+    fields: HashMap<String, Vec<(String, String)>>,
+    builtins: HashMap<String, Signature>,
+    type_checker: TypeChecker,
+    // (function name, return type) of the enclosing functions,
+    // loops push a dynamic one since a `return` inside of them only exits the loop.
+    returns: Vec<(String, String)>,
+    // function bodies are checked at the end of the scope they're declared in,
+    // so they can refer to anything declared alongside them. (scope depth, FnDeclStmnt)
+    pending: Vec<(usize, Rc<Node>)>,
+    // the depth of the scope holding the fields of the struct being declared, if any.
+    field_scope: Option<usize>,
+    pub diagnostics: Vec<ScorchError>,
+    // where the nodes being checked came from, set by whoever parsed them.
+    pub spans: Spans,
+}
+
+impl Analyzer {
+    pub fn new() -> Analyzer {
+        let mut analyzer = Analyzer::with_builtins(&get_builtin_functions());
+        analyzer.declare(
+            "none",
+            Symbol {
+                typename: NONE_TNAME.to_string(),
+                mutable: false,
+                signature: None,
+            },
+        );
+        analyzer
+    }
+    // knows about everything the interpreter has defined so far, including host functions & globals.
+    pub fn for_interpreter(interpreter: &Interpreter) -> Analyzer {
+        let mut analyzer = Analyzer::with_builtins(&interpreter.builtin);
+
+        for (id, var) in interpreter.context.borrow().variables.iter() {
+            let var = var.borrow();
+            let symbol = Symbol {
+                typename: var.m_type.borrow().name.clone(),
+                mutable: var.mutable,
+                signature: None,
+            };
+            analyzer.declare(id, symbol);
+        }
+        analyzer
+    }
+    fn with_builtins(builtins: &HashMap<String, StandardFunction>) -> Analyzer {
+        Analyzer {
+            scopes: vec![HashMap::new()],
+            structs: HashMap::new(),
+            fields: HashMap::new(),
+            builtins: builtins
+                .iter()
+                .map(|(id, builtin)| (id.clone(), builtin.signature.clone()))
+                .collect(),
+            type_checker: TypeChecker::new(),
+            returns: Vec::new(),
+            pending: Vec::new(),
+            field_scope: None,
+            diagnostics: Vec::new(),
+            spans: Spans::new(),
+        }
+    }
+    // checks a whole program, returning every diagnostic found.
+    pub fn check(&mut self, root: &Node) -> Vec<ScorchError> {
+        root.accept(self);
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    fn report(&mut self, err: ScorchError) {
+        self.diagnostics.push(err);
+    }
+    // diagnostics reported since `start` that don't know where they happened, happened in `node`.
+    fn locate_since(&mut self, start: usize, node: &Node) {
+        let location = self.spans.location(node).cloned();
+        for err in &mut self.diagnostics[start..] {
+            if err.location.is_none() {
+                err.location = location.clone();
+            }
+        }
+    }
+    fn lookup(&self, id: &str) -> Option<&Symbol> {
+        self.scopes.iter().rev().find_map(|scope| scope.get(id))
+    }
+    fn declare(&mut self, id: &str, symbol: Symbol) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(id.to_string(), symbol);
+        }
+    }
+    fn is_type(&self, typename: &str) -> bool {
+        self.structs.contains_key(typename) || self.type_checker.get(typename).is_some()
+    }
+    fn compatible(expected: &str, actual: &str) -> bool {
+        expected == DYNAMIC_TNAME || actual == DYNAMIC_TNAME || expected == actual
+    }
+    // builtin types, none of them have operators beyond what the interpreter implements directly.
+    fn is_primitive(typename: &str) -> bool {
+        [INT_TNAME, DOUBLE_TNAME, STRING_TNAME, BOOL_TNAME, ARRAY_TNAME, FN_TNAME, NONE_TNAME].contains(&typename)
+    }
+
+    fn statements(&mut self, statements: &Vec<Box<Node>>) {
+        for statement in statements {
+            let start = self.diagnostics.len();
+            statement.accept(self);
+            self.locate_since(start, statement);
+        }
+    }
+    fn scoped_block(&mut self, block: &Node) {
+        self.scopes.push(HashMap::new());
+        if let Node::Block(statements) = block {
+            self.statements(statements);
+        }
+        self.check_pending(self.scopes.len());
+        self.scopes.pop();
+    }
+    fn check_pending(&mut self, depth: usize) {
+        while let Some(index) = self.pending.iter().position(|(d, _)| *d == depth) {
+            let (_, declaration) = self.pending.remove(index);
+            let Node::FnDeclStmnt { id, params, body, return_type, .. } = declaration.as_ref() else {
+                continue;
+            };
+
+            let start = self.diagnostics.len();
+            self.scopes.push(HashMap::new());
+
+            for (name, typename) in params.iter().filter_map(param_parts) {
+                let symbol = Symbol {
+                    typename,
+                    mutable: false,
+                    signature: None,
+                };
+                self.declare(&name, symbol);
+            }
+            self.returns.push((id.clone(), return_type.clone()));
+            body.accept(self);
+            self.returns.pop();
+
+            self.scopes.pop();
+            self.locate_since(start, &declaration);
+        }
+    }
+
+    fn signature_of(&mut self, id: &str, params: &Vec<Node>, return_type: &str) -> Signature {
+        let params: Vec<String> = params.iter().filter_map(param_parts).map(|(_, typename)| typename).collect();
+
+        for typename in params.iter().chain(std::iter::once(&return_type.to_string())) {
+            if !self.is_type(typename) {
+                self.report(
+                    ScorchError::new(
+                        ErrorKind::UndefinedType,
+                        format!("{typename} used in the signature of {id} is not a known type"),
+                    )
+                    .with_subject(typename),
+                );
+            }
+        }
+        Signature {
+            params: Some(params),
+            return_type: return_type.to_string(),
+        }
+    }
+    fn check_args(&mut self, name: &str, signature: &Signature, args: &Vec<String>) {
+        let Some(params) = &signature.params else {
+            return;
+        };
+        if params.len() != args.len() {
+            self.report(
+                ScorchError::new(
+                    ErrorKind::ArgumentCount,
+                    format!("{name} expects {} arguments, got {}", params.len(), args.len()),
+                )
+                .with_subject(name),
+            );
+            return;
+        }
+        for (index, (param, arg)) in params.iter().zip(args.iter()).enumerate() {
+            if !Analyzer::compatible(param, arg) {
+                self.report(
+                    ScorchError::new(
+                        ErrorKind::TypeMismatch,
+                        format!("{name} expected argument {index} to be {param}, got {arg}"),
+                    )
+                    .with_subject(name),
+                );
+            }
+        }
+    }
+    fn arguments(&mut self, arguments: &Option<Vec<Node>>) -> Vec<String> {
+        match arguments {
+            Some(arguments) => arguments.iter().map(|arg| arg.accept(self)).collect(),
+            None => Vec::new(),
+        }
+    }
+    fn call(&mut self, id: &str, args: Vec<String>) -> String {
+        let signature = match self.lookup(id) {
+            Some(Symbol { signature: Some(signature), .. }) => signature.clone(),
+            // a parameter or variable holding some function, it could be anything.
+            Some(symbol) if Analyzer::compatible(FN_TNAME, &symbol.typename) => {
+                return DYNAMIC_TNAME.to_string();
+            }
+            Some(symbol) => {
+                let typename = symbol.typename.clone();
+                self.report(
+                    ScorchError::new(ErrorKind::TypeMismatch, format!("{id} is {typename}, not a function"))
+                        .with_subject(id),
+                );
+                return DYNAMIC_TNAME.to_string();
+            }
+            None => match self.builtins.get(id) {
+                Some(signature) => signature.clone(),
+                None => {
+                    self.report(
+                        ScorchError::new(ErrorKind::UndefinedFunction, format!("function {id} not found"))
+                            .with_subject(id),
+                    );
+                    return DYNAMIC_TNAME.to_string();
+                }
+            },
+        };
+        self.check_args(id, &signature, &args);
+        signature.return_type
+    }
+    fn member(&mut self, typename: &str, id: &str) -> Option<Symbol> {
+        let members = self.structs.get(typename)?;
+        let member = members.get(id).cloned();
+
+        if member.is_none() {
+            self.report(
+                ScorchError::new(ErrorKind::UndefinedVariable, format!("{typename} has no member {id}"))
+                    .with_subject(id),
+            );
+        }
+        member
+    }
+    // the struct's overload for `op`, checked like a method call with the rhs as its argument.
+    fn overload(&mut self, lhs_type: &str, op: &TokenKind, rhs_type: &str) -> Option<String> {
+        let (id, member) = self
+            .structs
+            .get(lhs_type)?
+            .iter()
+            .find(|(id, _)| overloaded_operator(id).as_ref() == Some(op))
+            .map(|(id, member)| (id.clone(), member.clone()))?;
+
+        let Some(signature) = member.signature else {
+            return Some(DYNAMIC_TNAME.to_string());
+        };
+        self.check_args(&format!("{lhs_type}.{id}"), &signature, &vec![lhs_type.to_string(), rhs_type.to_string()]);
+        Some(signature.return_type)
+    }
+    // checks the arguments of `new Type(..)` the way Interpreter::construct applies them.
+    fn construct(&mut self, typename: &str, arguments: &Option<Vec<Node>>) {
+        let fields = self.fields.get(typename).cloned();
+        let init = self.structs.get(typename).and_then(|members| members.get("init")).cloned();
+        let declared_type = |name: &str| {
+            let fields = fields.as_ref()?;
+            fields.iter().find(|(field, _)| field == name).map(|(_, typename)| typename.clone())
+        };
+
+        let mut positional = Vec::new();
+        for arg in arguments.iter().flatten() {
+            let Some((name, expression)) = named_argument(arg) else {
+                positional.push(arg.accept(self));
+                continue;
+            };
+            let actual = expression.accept(self);
+            match declared_type(name) {
+                Some(expected) if !Analyzer::compatible(&expected, &actual) => self.report(
+                    ScorchError::new(ErrorKind::TypeMismatch, format!("{typename}.{name} is {expected}, got {actual}"))
+                        .with_subject(name),
+                ),
+                Some(_) => {}
+                None if fields.is_some() => self.report(
+                    ScorchError::new(ErrorKind::UndefinedVariable, format!("{typename} has no field {name}"))
+                        .with_subject(name),
+                ),
+                None => {}
+            }
+        }
+
+        if let Some(init) = init {
+            if let Some(signature) = init.signature {
+                let mut args = vec![typename.to_string()];
+                args.extend(positional);
+                self.check_args(&format!("{typename}.init"), &signature, &args);
+            }
+            return;
+        }
+        // structs the analyzer didn't see declared can't be checked.
+        let Some(fields) = &fields else {
+            return;
+        };
+        if positional.len() > fields.len() {
+            self.report(
+                ScorchError::new(
+                    ErrorKind::ArgumentCount,
+                    format!("{typename} has {} fields, got {} arguments", fields.len(), positional.len()),
+                )
+                .with_subject(typename),
+            );
+            return;
+        }
+        for ((name, expected), actual) in fields.iter().zip(positional.iter()) {
+            if !Analyzer::compatible(expected, actual) {
+                self.report(
+                    ScorchError::new(ErrorKind::TypeMismatch, format!("{typename}.{name} is {expected}, got {actual}"))
+                        .with_subject(name),
+                );
+            }
+        }
+    }
+    fn dot(&mut self, lhs: &Node, rhs: &Node) -> String {
+        let lhs_type = lhs.accept(self);
+
+        match rhs {
+            Node::Identifier(id) => {
+                if let Some(member) = self.member(&lhs_type, id) {
+                    return member.typename;
+                }
+                if lhs_type != DYNAMIC_TNAME && !self.structs.contains_key(&lhs_type) {
+                    self.report(
+                        ScorchError::new(ErrorKind::TypeMismatch, format!("expected struct, {id} accessed on {lhs_type}"))
+                            .with_subject(id),
+                    );
+                }
+                DYNAMIC_TNAME.to_string()
+            }
+            Node::FunctionCall { id, arguments } => {
+                // the value on the left is passed as the first argument.
+                let mut args = vec![lhs_type.clone()];
+                args.extend(self.arguments(arguments));
+
+                if self.structs.contains_key(&lhs_type) {
+                    let Some(member) = self.member(&lhs_type, id) else {
+                        return DYNAMIC_TNAME.to_string();
+                    };
+                    let Some(signature) = member.signature else {
+                        // a field, which is called without the struct if it holds a function.
+                        if !Analyzer::compatible(FN_TNAME, &member.typename) {
+                            self.report(
+                                ScorchError::new(
+                                    ErrorKind::TypeMismatch,
+                                    format!("{lhs_type}.{id} is {}, not a function", member.typename),
+                                )
+                                .with_subject(id),
+                            );
+                        }
+                        return DYNAMIC_TNAME.to_string();
+                    };
+                    self.check_args(&format!("{lhs_type}.{id}"), &signature, &args);
+                    return signature.return_type;
+                }
+                // could be a struct at runtime, in which case an associated function is called.
+                if lhs_type == DYNAMIC_TNAME {
+                    return DYNAMIC_TNAME.to_string();
+                }
+                self.call(id, args)
+            }
+            _ => {
+                rhs.accept(self);
+                DYNAMIC_TNAME.to_string()
+            }
+        }
+    }
+    // the variable or field being assigned to.
+    fn place(&mut self, node: &Node) -> Option<(String, Symbol)> {
+        match node {
+            Node::Identifier(id) => match self.lookup(id) {
+                Some(symbol) => Some((id.clone(), symbol.clone())),
+                None => {
+                    self.report(
+                        ScorchError::new(ErrorKind::UndefinedVariable, format!("Variable {id} not found")).with_subject(id),
+                    );
+                    None
+                }
+            },
+            Node::BinaryOperation { lhs, op: TokenKind::Dot, rhs } => {
+                let Node::Identifier(id) = rhs.as_ref() else {
+                    node.accept(self);
+                    return None;
+                };
+                let lhs_type = lhs.accept(self);
+                self.member(&lhs_type, id).map(|member| (id.clone(), member))
+            }
+            _ => {
+                node.accept(self);
+                None
+            }
+        }
+    }
+    fn condition(&mut self, condition: &Node) {
+        let typename = condition.accept(self);
+        if !Analyzer::compatible(BOOL_TNAME, &typename) {
+            self.report(ScorchError::new(
+                ErrorKind::TypeMismatch,
+                format!("Expected boolean condition, got {typename}"),
+            ));
+        }
+    }
+    fn loop_body(&mut self, block: &Node) {
+        self.returns.push((String::new(), DYNAMIC_TNAME.to_string()));
+        block.accept(self);
+        self.returns.pop();
+    }
+}
+
+fn param_parts(param: &Node) -> Option<(String, String)> {
+    let Node::ParamDeclNode { varname, typename } = param else {
+        return None;
+    };
+    match (varname.as_ref(), typename.as_ref()) {
+        (Node::Identifier(name), Node::Identifier(typename)) => Some((name.clone(), typename.clone())),
+        _ => None,
+    }
+}
+
+impl Visitor<String> for Analyzer {
+    fn visit_program(&mut self, node: &Node) -> String {
+        if let Node::Program(statements) = node {
+            self.statements(statements);
+            self.check_pending(self.scopes.len());
+        }
+        NONE_TNAME.to_string()
+    }
+    fn visit_block(&mut self, node: &Node) -> String {
+        self.scoped_block(node);
+        NONE_TNAME.to_string()
+    }
+    fn visit_if_stmnt(&mut self, node: &Node) -> String {
+        if let Node::IfStmnt { condition, block, else_stmnt } = node {
+            self.condition(condition);
+            block.accept(self);
+            if let Some(else_stmnt) = else_stmnt {
+                else_stmnt.accept(self);
+            }
+        }
+        NONE_TNAME.to_string()
+    }
+    fn visit_else_stmnt(&mut self, node: &Node) -> String {
+        if let Node::ElseStmnt { condition, block, else_stmnt } = node {
+            if let Some(condition) = condition {
+                self.condition(condition);
+            }
+            block.accept(self);
+            if let Some(else_stmnt) = else_stmnt {
+                else_stmnt.accept(self);
+            }
+        }
+        NONE_TNAME.to_string()
+    }
+    fn visit_declaration(&mut self, node: &Node) -> String {
+        let Node::DeclStmt { target_type, id, expression, mutable } = node else {
+            return NONE_TNAME.to_string();
+        };
+        let actual = expression.accept(self);
+
+        if !self.is_type(target_type) {
+            self.report(
+                ScorchError::new(ErrorKind::UndefinedType, format!("{} is not a supported or known type.", target_type))
+                    .with_subject(target_type),
+            );
+        } else if !Analyzer::compatible(target_type, &actual) {
+            self.report(
+                ScorchError::new(
+                    ErrorKind::TypeMismatch,
+                    format!("invalid type in declaration '{id} : {target_type}', recieved {actual}"),
+                )
+                .with_subject(id),
+            );
+        }
+
+        // like the interpreter, a declaration can't shadow anything visible. fields are only checked
+        // against each other, each instance has its own.
+        let redefined = if self.field_scope == Some(self.scopes.len()) {
+            self.scopes.last().map_or(false, |scope| scope.contains_key(id))
+        } else {
+            self.lookup(id).is_some()
+        };
+        if redefined {
+            self.report(ScorchError::new(ErrorKind::Redefinition, format!("redefinition of variable {id}")).with_subject(id));
+        }
+
+        // a constant keeps the type of its value, a mutable dynamic variable can hold anything.
+        let typename = if target_type == DYNAMIC_TNAME && !*mutable {
+            actual
+        } else {
+            target_type.clone()
+        };
+        let symbol = Symbol {
+            typename,
+            mutable: *mutable,
+            signature: None,
+        };
+        self.declare(id, symbol);
+        NONE_TNAME.to_string()
+    }
+    fn visit_assignment(&mut self, node: &Node) -> String {
+        let Node::AssignStmnt { id, expression } = node else {
+            return NONE_TNAME.to_string();
+        };
+        let actual = expression.accept(self);
+
+        let Some((name, symbol)) = self.place(id) else {
+            return NONE_TNAME.to_string();
+        };
+        if !symbol.mutable {
+            self.report(ScorchError::new(ErrorKind::Immutable, format!("cannot assign to const : {name}")).with_subject(&name));
+        } else if !Analyzer::compatible(&symbol.typename, &actual) {
+            self.report(
+                ScorchError::new(
+                    ErrorKind::TypeMismatch,
+                    format!("Invalid type {}, recieved {actual}", symbol.typename),
+                )
+                .with_subject(&name),
+            );
+        }
+        NONE_TNAME.to_string()
+    }
+    fn visit_identifier(&mut self, node: &Node) -> String {
+        let Node::Identifier(id) = node else {
+            return DYNAMIC_TNAME.to_string();
+        };
+        match self.lookup(id) {
+            Some(symbol) => symbol.typename.clone(),
+            None => {
+                self.report(ScorchError::new(ErrorKind::UndefinedVariable, format!("Variable {id} not found")).with_subject(id));
+                DYNAMIC_TNAME.to_string()
+            }
+        }
+    }
+    fn visit_bool(&mut self, _node: &Node) -> String {
+        BOOL_TNAME.to_string()
+    }
+    fn visit_number(&mut self, node: &Node) -> String {
+        match node {
+            Node::Int(..) => INT_TNAME.to_string(),
+            _ => DOUBLE_TNAME.to_string(),
+        }
+    }
+    fn visit_string(&mut self, _node: &Node) -> String {
+        STRING_TNAME.to_string()
+    }
+    fn visit_eof(&mut self, _node: &Node) -> String {
+        NONE_TNAME.to_string()
+    }
+    fn visit_not_op(&mut self, node: &Node) -> String {
+        if let Node::NotOp(operand) = node {
+            let typename = operand.accept(self);
+            if !Analyzer::compatible(BOOL_TNAME, &typename) {
+                self.report(ScorchError::new(
+                    ErrorKind::TypeMismatch,
+                    format!("Expected boolean operand for unary not (!) operation, got {typename}"),
+                ));
+            }
+        }
+        BOOL_TNAME.to_string()
+    }
+    fn visit_neg_op(&mut self, node: &Node) -> String {
+        let Node::NegOp(operand) = node else {
+            return DYNAMIC_TNAME.to_string();
+        };
+        let typename = operand.accept(self);
+
+        if typename == INT_TNAME || typename == DOUBLE_TNAME || typename == DYNAMIC_TNAME {
+            return typename;
+        }
+        self.report(ScorchError::new(
+            ErrorKind::TypeMismatch,
+            format!("Expected numeric operand for unary negation (-) operation, got {typename}"),
+        ));
+        DYNAMIC_TNAME.to_string()
+    }
+    fn visit_relational_expression(&mut self, node: &Node) -> String {
+        let Node::RelationalExpression { lhs, op, rhs } = node else {
+            return BOOL_TNAME.to_string();
+        };
+        let lhs_type = lhs.accept(self);
+        let rhs_type = rhs.accept(self);
+
+        if self.overload(&lhs_type, op, &rhs_type).is_some() {
+            return BOOL_TNAME.to_string();
+        }
+
+        let is_number = |typename: &str| typename == INT_TNAME || typename == DOUBLE_TNAME;
+        let equality = matches!(op, TokenKind::Equals | TokenKind::NotEquals);
+
+        let valid = (is_number(&lhs_type) && is_number(&rhs_type))
+            || (equality && lhs_type == rhs_type && [BOOL_TNAME, STRING_TNAME, ARRAY_TNAME].contains(&lhs_type.as_str()));
+
+        if !valid && Analyzer::is_primitive(&lhs_type) && Analyzer::is_primitive(&rhs_type) {
+            self.report(ScorchError::new(
+                ErrorKind::InvalidOperator,
+                format!("invalid operator {:?} between {lhs_type} and {rhs_type}", op),
+            ));
+        }
+        BOOL_TNAME.to_string()
+    }
+    fn visit_logical_expression(&mut self, node: &Node) -> String {
+        if let Node::LogicalExpression { lhs, op, rhs } = node {
+            for operand in [lhs, rhs] {
+                let typename = operand.accept(self);
+                if Analyzer::is_primitive(&typename) && typename != BOOL_TNAME {
+                    self.report(ScorchError::new(
+                        ErrorKind::TypeMismatch,
+                        format!("invalid operand {typename} for logical operator {:?}", op),
+                    ));
+                }
+            }
+        }
+        BOOL_TNAME.to_string()
+    }
+    fn visit_expression(&mut self, node: &Node) -> String {
+        match node {
+            Node::Expression(root) => root.accept(self),
+            _ => DYNAMIC_TNAME.to_string(),
+        }
+    }
+    fn visit_binary_op(&mut self, node: &Node) -> String {
+        let Node::BinaryOperation { lhs, op, rhs } = node else {
+            return DYNAMIC_TNAME.to_string();
+        };
+        if let TokenKind::Dot = op {
+            return self.dot(lhs, rhs);
+        }
+
+        let lhs_type = lhs.accept(self);
+        let rhs_type = rhs.accept(self);
+
+        let is_number = |typename: &str| typename == INT_TNAME || typename == DOUBLE_TNAME;
+        let bitwise = matches!(
+            op,
+            TokenKind::BitwiseAnd | TokenKind::BitwiseOr | TokenKind::BitwiseXor | TokenKind::LeftShift | TokenKind::RightShift
+        );
+
+        if lhs_type == INT_TNAME && rhs_type == INT_TNAME {
+            return lhs_type;
+        }
+        // ints are promoted when mixed with doubles, bitwise operators only work on ints though.
+        if is_number(&lhs_type) && is_number(&rhs_type) && !bitwise {
+            return DOUBLE_TNAME.to_string();
+        }
+        if lhs_type == STRING_TNAME && rhs_type == STRING_TNAME && matches!(op, TokenKind::Add) {
+            return lhs_type;
+        }
+        if let Some(return_type) = self.overload(&lhs_type, op, &rhs_type) {
+            return return_type;
+        }
+        // anything else needs an operator overload, which builtin types don't have.
+        if Analyzer::is_primitive(&lhs_type) && Analyzer::is_primitive(&rhs_type) {
+            self.report(ScorchError::new(
+                ErrorKind::InvalidOperator,
+                format!("no operator {:?} for {lhs_type} and {rhs_type}", op),
+            ));
+        }
+        DYNAMIC_TNAME.to_string()
+    }
+    fn visit_term(&mut self, _node: &Node) -> String {
+        NONE_TNAME.to_string()
+    }
+    fn visit_factor(&mut self, node: &Node) -> String {
+        self.visit_expression(node)
+    }
+    fn visit_function_call(&mut self, node: &Node) -> String {
+        let Node::FunctionCall { id, arguments } = node else {
+            return DYNAMIC_TNAME.to_string();
+        };
+        let args = self.arguments(arguments);
+        self.call(id, args)
+    }
+    fn visit_function_decl(&mut self, node: &Node) -> String {
+        if let Node::FnDeclStmnt { id, params, return_type, mutable, .. } = node {
+            // anonymous functions are values, there's nothing to declare.
+            if id.is_empty() {
+                self.signature_of(id, params, return_type);
+                let declaration = Rc::new(node.clone());
+                self.spans.copy(node, &declaration);
+                self.pending.push((self.scopes.len(), declaration));
+                return FN_TNAME.to_string();
+            }
+            let signature = self.signature_of(id, params, return_type);
+            let symbol = Symbol {
+                typename: FN_TNAME.to_string(),
+                mutable: *mutable,
+                signature: Some(signature),
+            };
+            self.declare(id, symbol);
+            let declaration = Rc::new(node.clone());
+            self.spans.copy(node, &declaration);
+            self.pending.push((self.scopes.len(), declaration));
+        }
+        NONE_TNAME.to_string()
+    }
+    fn visit_repeat_stmnt(&mut self, node: &Node) -> String {
+        let Node::RepeatStmnt { iterator_id, condition, block } = node else {
+            return DYNAMIC_TNAME.to_string();
+        };
+        self.scopes.push(HashMap::new());
+
+        if let Some(id) = iterator_id {
+            match self.lookup(id) {
+                Some(symbol) if !symbol.mutable => {
+                    self.report(
+                        ScorchError::new(
+                            ErrorKind::Immutable,
+                            format!("Cannot mutate immutable variable {} in a repeat loop", id),
+                        )
+                        .with_subject(id),
+                    );
+                }
+                Some(_) => {}
+                None => {
+                    let symbol = Symbol {
+                        typename: INT_TNAME.to_string(),
+                        mutable: true,
+                        signature: None,
+                    };
+                    self.declare(id, symbol);
+                }
+            }
+        }
+        if let Some(condition) = condition {
+            self.condition(condition);
+        }
+        self.loop_body(block);
+
+        self.scopes.pop();
+        // whatever the loop returns, if anything.
+        DYNAMIC_TNAME.to_string()
+    }
+    fn visit_break_stmnt(&mut self, node: &Node) -> String {
+        let Node::ReturnStmnt(value) = node else {
+            return NONE_TNAME.to_string();
+        };
+        let actual = match value {
+            Some(value) => value.accept(self),
+            None => NONE_TNAME.to_string(),
+        };
+
+        if let Some((function, expected)) = self.returns.last().cloned() {
+            if !Analyzer::compatible(&expected, &actual) {
+                self.report(
+                    ScorchError::new(
+                        ErrorKind::TypeMismatch,
+                        format!("{function} should return {expected}, but returns {actual}"),
+                    )
+                    .with_subject(function),
+                );
+            }
+        }
+        NONE_TNAME.to_string()
+    }
+    fn visit_array(&mut self, node: &Node) -> String {
+        if let Node::Array { elements, .. } = node {
+            for element in elements {
+                element.accept(self);
+            }
+        }
+        ARRAY_TNAME.to_string()
+    }
+    fn visit_array_access(&mut self, node: &Node) -> String {
+        let Node::ArrayAccessExpr { id, index_expr, expression, .. } = node else {
+            return DYNAMIC_TNAME.to_string();
+        };
+
+        match self.lookup(id).map(|symbol| symbol.typename.clone()) {
+            Some(typename) if !Analyzer::compatible(ARRAY_TNAME, &typename) => {
+                self.report(ScorchError::new(ErrorKind::TypeMismatch, format!("cannot index {id}, it is {typename}")).with_subject(id));
+            }
+            Some(_) => {}
+            None => {
+                self.report(ScorchError::new(ErrorKind::UndefinedVariable, format!("Variable {id} not found")).with_subject(id));
+            }
+        }
+
+        let index = index_expr.accept(self);
+        if !(index == INT_TNAME || index == DOUBLE_TNAME || index == DYNAMIC_TNAME) {
+            self.report(
+                ScorchError::new(ErrorKind::TypeMismatch, format!("Expected numerical index value, got {index}")).with_subject(id),
+            );
+        }
+        if let Some(expression) = expression {
+            expression.accept(self);
+        }
+        DYNAMIC_TNAME.to_string()
+    }
+    fn visit_struct_def(&mut self, node: &Node) -> String {
+        let Node::StructDecl { id, block } = node else {
+            return NONE_TNAME.to_string();
+        };
+
+        self.scopes.push(HashMap::new());
+        if let Node::Block(statements) = block.as_ref() {
+            let outer = self.field_scope.replace(self.scopes.len());
+            self.statements(statements);
+            self.field_scope = outer;
+
+            let declared = statements
+                .iter()
+                .filter_map(|statement| match statement.as_ref() {
+                    Node::DeclStmt { target_type, id, .. } => Some((id.clone(), target_type.clone())),
+                    _ => None,
+                })
+                .collect();
+            self.fields.insert(id.clone(), declared);
+        }
+        self.check_pending(self.scopes.len());
+        let fields = self.scopes.pop().unwrap_or_default();
+
+        self.structs.insert(id.clone(), fields);
+        NONE_TNAME.to_string()
+    }
+    fn visit_struct_init(&mut self, node: &Node) -> String {
+        let Node::StructInit { id, arguments } = node else {
+            return DYNAMIC_TNAME.to_string();
+        };
+        if !self.is_type(id) {
+            self.report(ScorchError::new(ErrorKind::UndefinedType, format!("Struct {} not found", id)).with_subject(id));
+            return DYNAMIC_TNAME.to_string();
+        }
+        self.construct(id, arguments);
+        id.clone()
+    }
+    fn visit_type_assoc_block(&mut self, node: &Node) -> String {
+        let Node::TypeAssocBlock { typename, block } = node else {
+            return NONE_TNAME.to_string();
+        };
+        if !self.is_type(typename) {
+            self.report(
+                ScorchError::new(ErrorKind::UndefinedType, format!("Struct {} not found", typename)).with_subject(typename),
+            );
+        }
+
+        // the functions declared here become members of the type, outside of the block
+        // they're only reachable through a value of it, inside they can call each other by name.
+        self.scopes.push(HashMap::new());
+        let depth = self.scopes.len();
+        if let Node::Block(statements) = block.as_ref() {
+            self.statements(statements);
+        }
+        let associated = self.scopes.last().cloned().unwrap_or_default();
+
+        for (id, symbol) in &associated {
+            let params = symbol.signature.as_ref().and_then(|signature| signature.params.as_ref());
+            if let (Some(_), Some(params)) = (overloaded_operator(id), params) {
+                if params.len() != 2 {
+                    self.report(
+                        ScorchError::new(
+                            ErrorKind::ArgumentCount,
+                            format!("operator {id} should take 2 parameters (this : {typename}, rhs), got {}", params.len()),
+                        )
+                        .with_subject(id),
+                    );
+                }
+            }
+        }
+
+        if let Some(members) = self.structs.get_mut(typename) {
+            members.extend(associated);
+        }
+        self.check_pending(depth);
+        self.scopes.pop();
+        NONE_TNAME.to_string()
+    }
+}
@@ -3,24 +3,129 @@ use std::fs::File;
 use std::io::{self, Read, Write};
 use std::rc::Rc;
 
+use crate::analyzer::Analyzer;
+use crate::config::InterpreterConfig;
 use crate::context::Context;
+use crate::error::{ErrorKind, ScorchError, ScorchResult};
+use crate::types::Value;
 use crate::*;
 use scorch_parser::*;
 
+pub enum Command {
+    Run(String),
+    Repl,
+    Check(String),
+    Dump(String),
+}
+
+pub const USAGE: &str = "usage: scorch [flags] [command]
+
+commands:
+    run <file> [-- args]    runs a script, scorch_src/main.scorch when no command is given
+    repl                    starts an interactive session
+    check <file>            reports problems in a script without running it
+    no-interpret [file]     same as check, scorch_src/main.scorch when no file is given
+    dump <file>             runs a script, then prints its tokens, ast and globals
+
+flags:
+    --help                  prints this message
+    --sandbox               denies scripts input, time, terminal and process access
+    --no-interpret          checks the script instead of running it
+    --max-steps <n>         stops a script after n evaluation steps";
+
 pub struct Flags {
     pub proj_root: String,
-    pub dump: bool,
-    pub cli: bool,
-    pub no_interpret: bool,
+    pub command: Command,
+    // everything after `--`, scripts get these from args().
+    pub script_args: Vec<String>,
+    pub sandbox: bool,
+    pub max_steps: Option<u64>,
+    pub help: bool,
 }
 impl Flags {
-    pub fn new(flags_map: HashMap<String, bool>) -> Flags {
-        Flags {
-            proj_root: get_project_root(),
-            dump: flags_map.contains_key("dump"),
-            cli: flags_map.contains_key("cli"),
-            no_interpret: flags_map.contains_key("no-interpret"),
+    pub fn parse(args: &[String]) -> Result<Flags, String> {
+        let mut positional = Vec::new();
+        let mut script_args = Vec::new();
+        let mut sandbox = false;
+        let mut max_steps = None;
+        let mut help = false;
+        let mut no_interpret = false;
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                script_args.extend(args.by_ref().cloned());
+                break;
+            }
+            let Some(flag) = arg.strip_prefix("--") else {
+                positional.push(arg.clone());
+                continue;
+            };
+            // both `--flag value` and `--flag=value` work.
+            let (name, value) = match flag.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (flag, None),
+            };
+            match name {
+                "help" => help = true,
+                "sandbox" => sandbox = true,
+                "no-interpret" => no_interpret = true,
+                "max-steps" => {
+                    let value = value.or_else(|| args.next().cloned()).unwrap_or_default();
+                    let Ok(steps) = value.parse::<u64>() else {
+                        return Err(format!("--max-steps expects a number, got '{}'", value));
+                    };
+                    max_steps = Some(steps);
+                }
+                _ => return Err(format!("unknown flag --{}", name)),
+            }
+        }
+
+        let proj_root = get_project_root();
+        let mut positional = positional.into_iter();
+        let expect_file = |arg: Option<String>, command: &str| arg.ok_or(format!("{} expects a file", command));
+
+        let main_file = format!("{}/{}", proj_root, "scorch_src/main.scorch");
+
+        let mut command = match positional.next().as_deref() {
+            None => Command::Run(main_file),
+            Some("run") => Command::Run(expect_file(positional.next(), "run")?),
+            Some("repl") => Command::Repl,
+            Some("check") => Command::Check(expect_file(positional.next(), "check")?),
+            // the word older versions used for check.
+            Some("no-interpret") => Command::Check(positional.next().unwrap_or(main_file)),
+            Some("dump") => Command::Dump(expect_file(positional.next(), "dump")?),
+            Some(other) => return Err(format!("unknown command {}", other)),
+        };
+
+        if let Some(extra) = positional.next() {
+            return Err(format!("unexpected argument {}", extra));
         }
+
+        if no_interpret {
+            command = match command {
+                Command::Run(file) | Command::Check(file) => Command::Check(file),
+                _ => return Err(String::from("--no-interpret only applies to running a file")),
+            };
+        }
+
+        Ok(Flags {
+            proj_root,
+            command,
+            script_args,
+            sandbox,
+            max_steps,
+            help,
+        })
+    }
+    pub fn config(&self) -> InterpreterConfig {
+        let mut config = if self.sandbox {
+            InterpreterConfig::sandboxed()
+        } else {
+            InterpreterConfig::default()
+        };
+        config.max_steps = self.max_steps;
+        config
     }
     pub fn qualify_from_root(&self, path: String) {
         let mut path = path;
@@ -38,9 +143,10 @@ pub fn get_project_root() -> String {
     project_root.to_str().unwrap().to_string()
 }
 
-pub fn run_repl() {
+// returns the process exit code, non zero only when a script exits with one.
+pub fn run_repl(config: InterpreterConfig) -> i32 {
     let mut tokenizer = lexer::create_tokenizer();
-    let mut interpreter = Interpreter::new();
+    let mut interpreter = Interpreter::with_config(config);
 
     let mut input = String::new();
 
@@ -51,7 +157,7 @@ pub fn run_repl() {
         io::stdin().read_line(&mut input).unwrap();
 
         if input.trim() == "exit" {
-            break;
+            return 0;
         }
 
         tokenizer.tokenize(&input.as_str());
@@ -65,47 +171,131 @@ pub fn run_repl() {
                 continue;
             };
             
-            dbg!(err);
-            panic!();
+            println!("Failed to parse input : {:?}", err);
+            input.clear();
+            continue;
         };
         
         
-        ast_root.accept(&mut interpreter);
+        // every input gets the whole step budget & timeout.
+        interpreter.reset_limits();
+        interpreter.spans.record(&input, None, tokens, &ast_root);
+        match ast_root.accept(&mut interpreter) {
+            Ok(Value::None()) => {}
+            Ok(value) => println!("{}", value),
+            Err(err) => match err.kind {
+                ErrorKind::Exit(code) => return code,
+                _ => println!("{}", err),
+            },
+        }
         
         input.clear();
     }
 }
-pub fn execute_from_file(filename: String) -> Rc<RefCell<Context>> {
-    let mut tokenizer = lexer::create_tokenizer();
-    let mut file = File::open(filename).expect("Failed to open file");
+pub fn read_source_file(filename: &String) -> ScorchResult<String> {
     let mut contents = String::new();
-    file.read_to_string(&mut contents)
-        .expect("Failed to read file");
+    let read = File::open(filename).and_then(|mut file| file.read_to_string(&mut contents));
+    if let Err(err) = read {
+        return Err(ScorchError::new(ErrorKind::Io, format!("Failed to read file : {}", err))
+            .with_subject(filename));
+    }
+    Ok(contents)
+}
+// runs whatever the command line asked for, returning the process exit code.
+pub fn run_command(flags: Flags) -> i32 {
+    if flags.help {
+        println!("{}", USAGE);
+        return 0;
+    }
+    match &flags.command {
+        Command::Repl => run_repl(flags.config()),
+        Command::Check(file) => run_check(file),
+        Command::Dump(file) => exit_code(execute_file_then_dump(file.clone(), flags.config())),
+        Command::Run(file) => {
+            let result = run_file(file, flags.script_args.clone(), flags.config());
+            exit_code(result.map(|_| ()))
+        }
+    }
+}
+fn exit_code(result: ScorchResult<()>) -> i32 {
+    match result {
+        Ok(()) => 0,
+        Err(err) => match err.kind {
+            ErrorKind::Exit(code) => code,
+            _ => {
+                eprintln!("{}", err);
+                1
+            }
+        },
+    }
+}
+pub fn execute_from_file(filename: String) -> ScorchResult<Rc<RefCell<Context>>> {
+    run_file(&filename, Vec::new(), InterpreterConfig::default())
+}
+pub fn run_file(filename: &String, script_args: Vec<String>, config: InterpreterConfig) -> ScorchResult<Rc<RefCell<Context>>> {
+    let mut tokenizer = lexer::create_tokenizer();
+    let contents = read_source_file(filename)?;
     tokenizer.tokenize(&contents.as_str());
 
     let tokens = tokenizer.tokens;
-    let mut interpreter = Interpreter::new();
+    let mut interpreter = Interpreter::with_config(config);
+    interpreter.script_args = script_args;
     
-    let ast_root = parser::parse_program(&tokens);
-        
-        let Ok(ast_root) = ast_root else {
-            let Err(err) = ast_root else {
-                panic!("Failed to parse input:");
-            };
-            
-            dbg!(err);
-            panic!();
-        };
-    ast_root.accept(&mut interpreter);
-    interpreter.context
+    let ast_root = match parser::parse_program(&tokens) {
+        Ok(root) => root,
+        Err(err) => {
+            return Err(ScorchError::new(ErrorKind::Parse, format!("Failed to parse input : {:?}", err))
+                .with_subject(filename));
+        }
+    };
+    interpreter.spans.record(&contents, Some(filename.as_str()), &tokens, &ast_root);
+    ast_root.accept(&mut interpreter)?;
+    Ok(interpreter.context)
 }
-pub fn execute_file_then_dump(filename: String) {
+// statically checks a file without executing it, returning every problem found.
+pub fn check_file(filename: &String) -> ScorchResult<Vec<ScorchError>> {
     let mut tokenizer = lexer::create_tokenizer();
-    let mut file = File::open(filename).expect("Failed to open file");
-    let mut contents = String::new();
+    let contents = read_source_file(filename)?;
+    tokenizer.tokenize(&contents.as_str());
+
+    let ast_root = match parser::parse_program(&tokenizer.tokens) {
+        Ok(root) => root,
+        Err(err) => {
+            return Err(ScorchError::new(ErrorKind::Parse, format!("Failed to parse input : {:?}", err))
+                .with_subject(filename));
+        }
+    };
+
+    let mut analyzer = Analyzer::new();
+    analyzer.spans.record(&contents, Some(filename.as_str()), &tokenizer.tokens, &ast_root);
+    Ok(analyzer.check(&ast_root))
+}
+// check only mode, prints every diagnostic and returns the process exit code.
+pub fn run_check(filename: &String) -> i32 {
+    let diagnostics = match check_file(filename) {
+        Ok(diagnostics) => diagnostics,
+        Err(err) => {
+            eprintln!("{}", err);
+            return 1;
+        }
+    };
+
+    for err in &diagnostics {
+        eprintln!("{}\n", err);
+    }
+
+    if diagnostics.is_empty() {
+        println!("{} : no problems found", filename);
+        0
+    } else {
+        eprintln!("{} : {} problem(s) found", filename, diagnostics.len());
+        1
+    }
+}
+pub fn execute_file_then_dump(filename: String, config: InterpreterConfig) -> ScorchResult<()> {
+    let mut tokenizer = lexer::create_tokenizer();
+    let contents = read_source_file(&filename)?;
 
-    file.read_to_string(&mut contents)
-        .expect("Failed to read file");
     tokenizer.tokenize(&contents.as_str());
     let tokens = tokenizer.tokens;
     println!("Tokens:");
@@ -113,14 +303,15 @@ pub fn execute_file_then_dump(filename: String) {
     let ast_root = parser::parse_program(&tokens);
     println!("AST Root:");
     dbg!(&ast_root);
-    let mut interpreter = Interpreter::new();
+    let mut interpreter = Interpreter::with_config(config);
     let Ok(ast_root) = ast_root else {
-        panic!("Failed to parse input");
+        return Err(ScorchError::new(ErrorKind::Parse, "Failed to parse input").with_subject(filename));
     };
     
-    ast_root.accept(&mut interpreter);
+    let result = ast_root.accept(&mut interpreter);
     println!("Global Context:");
     
     dbg!(interpreter.type_checker.types);
     dbg!(interpreter.context);
+    result.map(|_| ())
 }
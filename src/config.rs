@@ -0,0 +1,79 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+// groups of builtins with side effects outside the interpreter, a builtin
+// that requires a capability the config doesn't grant reports a permission error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Capability {
+    ConsoleInput,
+    TerminalControl,
+    Time,
+    Process,
+    FileSystem,
+}
+
+impl Capability {
+    pub const ALL: [Capability; 5] = [
+        Capability::ConsoleInput,
+        Capability::TerminalControl,
+        Capability::Time,
+        Capability::Process,
+        Capability::FileSystem,
+    ];
+}
+
+// the stack the default max_call_depth needs, in bytes. each scorch call takes a couple dozen rust
+// frames, which are large in debug builds, so a default sized thread (2 MiB, 8 MiB for the main
+// thread) overflows long before the limit. run the interpreter with `run_on_stack`, or lower the limit.
+pub const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+// runs `f` on a new thread with a stack of STACK_SIZE, the interpreter has to be created inside it.
+pub fn run_on_stack<T, F>(f: F) -> std::io::Result<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let handle = std::thread::Builder::new().stack_size(STACK_SIZE).spawn(f)?;
+    match handle.join() {
+        Ok(result) => Ok(result),
+        Err(panic) => std::panic::resume_unwind(panic),
+    }
+}
+
+// options for running untrusted scripts, every limit is optional.
+#[derive(Debug, Clone)]
+pub struct InterpreterConfig {
+    // the maximum number of ast nodes evaluated between calls to Interpreter::reset_limits.
+    pub max_steps: Option<u64>,
+    // the maximum number of nested scorch function calls.
+    pub max_call_depth: Option<usize>,
+    // wall clock time allowed, measured from the first node evaluated after a reset.
+    pub timeout: Option<Duration>,
+    pub capabilities: HashSet<Capability>,
+}
+
+impl InterpreterConfig {
+    // no side effecting builtins, for scripts from untrusted sources.
+    pub fn sandboxed() -> Self {
+        InterpreterConfig {
+            capabilities: HashSet::new(),
+            ..Default::default()
+        }
+    }
+    pub fn allows(&self, capability: Capability) -> bool {
+        self.capabilities.contains(&capability)
+    }
+}
+
+impl Default for InterpreterConfig {
+    fn default() -> Self {
+        InterpreterConfig {
+            max_steps: None,
+            // deep enough for ordinary recursion, and stops runaway recursion before it
+            // overflows a stack of STACK_SIZE.
+            max_call_depth: Some(2_000),
+            timeout: None,
+            capabilities: HashSet::from(Capability::ALL),
+        }
+    }
+}
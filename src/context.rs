@@ -1,13 +1,15 @@
 use std::{self};
 use std::cell::RefCell;
-use std::collections::HashMap;
+use indexmap::IndexMap;
 use std::rc::Rc;
 
-use super::types::{Instance, Value};
+use super::error::{ErrorKind, ScorchError, ScorchResult};
+use super::types::{Closure, Function, Instance, Value};
 
 pub struct Context {
     pub parent: Option<Rc<RefCell<Context>>>,
-    pub variables: HashMap<String, Rc<RefCell<Instance>>>,
+    // insertion ordered, so struct fields keep their declaration order.
+    pub variables: IndexMap<String, Rc<RefCell<Instance>>>,
 }
 
 impl std::fmt::Debug for Context {
@@ -31,7 +33,7 @@ impl Context {
     pub fn new() -> Rc<RefCell<Context>> {
         Rc::new(RefCell::new(Context {
             parent: Option::None,
-            variables: HashMap::new(),
+            variables: IndexMap::new(),
         }))
     }
     pub fn find_variable(&self, name: &str) -> Option<Rc<RefCell<Instance>>> {
@@ -43,6 +45,23 @@ impl Context {
             },
         }
     }
+    // called when execution leaves a scope for good. the functions declared in it stop keeping it alive,
+    // so it's dropped along with them, unless one of them escaped the scope and is still in use.
+    pub fn release(scope: &Rc<RefCell<Context>>) {
+        for var in scope.borrow().variables.values() {
+            let mut var = var.borrow_mut();
+            let Value::Function(function) = &var.value else {
+                continue;
+            };
+            match &function.closure {
+                Closure::Strong(closure) if Rc::ptr_eq(closure, scope) => {}
+                _ => continue,
+            }
+            let mut released = Function::clone(function);
+            released.closure = Closure::Weak(Rc::downgrade(scope));
+            var.value = Value::Function(Rc::new(released));
+        }
+    }
     pub fn insert_variable(&mut self, name: &str, value: Rc<RefCell<Instance>>) -> () {
         self.variables.insert(String::from(name), value);
     }
@@ -50,18 +69,33 @@ impl Context {
         &mut self,
         name: &str,
         value: &'ctx Value,
-    ) -> Result<(), ()> {
+    ) -> ScorchResult<()> {
         if let Some(var) = self.variables.get_mut(name) {
             if let Ok(mut v) = var.try_borrow_mut() {
+                if !v.m_type.borrow().validate(value) {
+                    return Err(ScorchError::new(
+                        ErrorKind::TypeMismatch,
+                        format!("cannot assign {:?} to {} : {}", value, name, v.m_type.borrow().name),
+                    )
+                    .with_subject(name));
+                }
                 v.set_value(value);
                 Ok(())
             } else {
-                panic!("INTERNAL_INTERPRETER_ERROR::\n\t error : ->-> double borrow on variable {}, value : {:?} <-<- : error ", name, value.clone());
+                Err(ScorchError::new(
+                    ErrorKind::Internal,
+                    format!("double borrow on variable {}, value : {:?}", name, value),
+                )
+                .with_subject(name))
             }
         } else if let Some(parent) = &self.parent {
             parent.borrow_mut().seek_overwrite_in_parents(name, value)
         } else {
-            Err(())
+            Err(ScorchError::new(
+                ErrorKind::UndefinedVariable,
+                format!("assignment error : {} not found.", name),
+            )
+            .with_subject(name))
         }
     }
 }
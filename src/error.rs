@@ -0,0 +1,133 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ErrorKind {
+    Parse,
+    UndefinedVariable,
+    UndefinedFunction,
+    UndefinedType,
+    Redefinition,
+    TypeMismatch,
+    ArgumentCount,
+    Immutable,
+    IndexOutOfBounds,
+    InvalidOperator,
+    DivisionByZero,
+    Overflow,
+    AssertionFailed,
+    StepLimitExceeded,
+    CallDepthExceeded,
+    Timeout,
+    PermissionDenied,
+    Io,
+    // a script called exit(code), not a failure unless the code says so.
+    Exit(i32),
+    Internal,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SourceLocation {
+    pub file: Option<String>,
+    // 1 based, like an editor.
+    pub line: usize,
+    pub column: usize,
+    pub length: usize,
+    pub source_line: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StackFrame {
+    // the function name, `Type.method` for associated functions.
+    pub function: String,
+    pub call_site: Option<SourceLocation>,
+}
+
+// every failure a script can cause ends up here instead of in a panic,
+// so hosts embedding the interpreter can recover from bad scripts.
+#[derive(Debug, Clone)]
+pub struct ScorchError {
+    pub kind: ErrorKind,
+    pub message: String,
+    // the identifier or value the error is about, if there is one.
+    pub subject: Option<String>,
+    pub location: Option<SourceLocation>,
+    // the scorch functions that were executing when the error occurred, innermost first.
+    pub trace: Vec<StackFrame>,
+}
+
+pub type ScorchResult<T = crate::types::Value> = Result<T, ScorchError>;
+
+impl ScorchError {
+    pub fn new(kind: ErrorKind, message: impl Into<String>) -> Self {
+        ScorchError {
+            kind,
+            message: message.into(),
+            subject: None,
+            location: None,
+            trace: Vec::new(),
+        }
+    }
+    pub fn with_subject(mut self, subject: impl Into<String>) -> Self {
+        self.subject = Some(subject.into());
+        self
+    }
+    // the error happened evaluating the node at `location`, the innermost node to say so wins.
+    // once the error has left a function, nodes locate the call site of that function instead.
+    pub fn at(mut self, location: Option<&SourceLocation>) -> Self {
+        let slot = match self.trace.last_mut() {
+            Some(frame) => &mut frame.call_site,
+            None => &mut self.location,
+        };
+        if slot.is_none() {
+            *slot = location.cloned();
+        }
+        self
+    }
+    pub fn leave_frame(mut self, function: &str) -> Self {
+        self.trace.push(StackFrame {
+            function: function.to_string(),
+            call_site: None,
+        });
+        self
+    }
+}
+
+impl fmt::Display for ScorchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} error : {}", self.kind, self.message)?;
+        if let Some(subject) = &self.subject {
+            write!(f, " ({})", subject)?;
+        }
+        if let Some(location) = &self.location {
+            let file = location.file.as_deref().unwrap_or("<input>");
+            let line_no = location.line.to_string();
+            let gutter = " ".repeat(line_no.len());
+
+            write!(f, "\n{}--> {}:{}:{}", gutter, file, location.line, location.column)?;
+            write!(f, "\n{} |", gutter)?;
+            write!(f, "\n{} | {}", line_no, location.source_line)?;
+            // keep tabs so the caret lines up with the source line above it.
+            let indent: String = location
+                .source_line
+                .chars()
+                .take(location.column - 1)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+            write!(f, "\n{} | {}{}", gutter, indent, "^".repeat(location.length.max(1)))?;
+        }
+        if !self.trace.is_empty() {
+            write!(f, "\ntraceback (most recent call last):")?;
+            for frame in self.trace.iter().rev() {
+                write!(f, "\n  in {}", frame.function)?;
+                if let Some(call_site) = &frame.call_site {
+                    let file = call_site.file.as_deref().unwrap_or("<input>");
+                    write!(f, ", called at {}:{}:{}", file, call_site.line, call_site.column)?;
+                    write!(f, "\n    {}", call_site.source_line.trim())?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ScorchError {}
@@ -1,19 +1,43 @@
+use super::config::InterpreterConfig;
 use super::context::Context;
+use super::error::{ErrorKind, ScorchError, ScorchResult, SourceLocation};
+use super::native::NativeFn;
+use super::span::Spans;
 use super::standard_functions::StandardFunction;
 use super::typechecker::*;
 use super::types::*;
 use scorch_parser::ast::*;
 use scorch_parser::lexer::*;
+use indexmap::IndexMap;
 use std::cell::RefCell;
 use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Write};
 
 
 use std::rc::Rc;
+use std::time::Instant;
 
 pub struct Interpreter {
     pub context: Rc<RefCell<Context>>, // initally the root context, but this is a kinda tree like structure.
     pub builtin: HashMap<String, StandardFunction>,
     pub type_checker: TypeChecker,
+    pub config: InterpreterConfig,
+    // every io builtin goes through these, so hosts can capture output or script input.
+    pub stdout: Box<dyn Write>,
+    pub stderr: Box<dyn Write>,
+    pub stdin: Box<dyn BufRead>,
+    // what args() returns, the command line arguments after `--` when run from the cli.
+    pub script_args: Vec<String>,
+    steps: u64,
+    // names of the scorch functions currently executing, innermost last.
+    call_stack: Vec<String>,
+    deadline: Option<Instant>,
+    // where the nodes being run came from, set by whoever parsed them.
+    pub spans: Spans,
+    // the spans of the function bodies & struct fields being run, innermost last.
+    body_spans: Vec<Rc<Spans>>,
+    // the scopes of `within` blocks, the associated functions declared in them only hold them weakly.
+    assoc_scopes: Vec<Rc<RefCell<Context>>>,
 }
 
 impl Interpreter {
@@ -22,37 +46,52 @@ impl Interpreter {
         id: &str,
         condition: &Option<Box<Node>>,
         block: &Box<Node>,
-    ) -> Value {
-        let mut iter: i32 = 0;
+    ) -> ScorchResult {
+        // the iterator lives in its own context, which has to be popped no matter how the loop exits.
+        self.push_ctx();
+        let result = self.repeat_with_iterator(id, condition, block);
+        self.pop_ctx()?;
+        result
+    }
+    fn repeat_with_iterator(
+        &mut self,
+        id: &str,
+        condition: &Option<Box<Node>>,
+        block: &Box<Node>,
+    ) -> ScorchResult {
+        let mut iter: i64 = 0;
 
         let typename = INT_TNAME.to_string();
-        
-        self.push_ctx();
-        
+
         {
             let mut ctx = self.context.borrow_mut();
-            
+
             match ctx.find_variable(&id) {
                 Some(v) => {
                     let v = v.borrow_mut();
-                    
+
                     if v.mutable == false {
-                        panic!("Cannot mutate immutable variable {} in a repeat loop", id);
+                        return Err(ScorchError::new(
+                            ErrorKind::Immutable,
+                            format!("Cannot mutate immutable variable {} in a repeat loop", id),
+                        )
+                        .with_subject(id));
                     }
                 }
                 None => {
                     let val = Value::Int(0);
                     let Some(m_type) = self.type_checker.get(INT_TNAME) else {
-                        panic!("Double isnt a type")
+                        return Err(ScorchError::new(ErrorKind::Internal, format!("{} isnt a type", INT_TNAME)));
                     };
-                    
+
                     let var = Instance::new(true, val, m_type);
-                    
+
                     if !TypeChecker::validate(&var) {
-                        panic!(
-                            "Invalid type for variable {} (generated by a 'repeat' loop)",
-                            id
-                        );
+                        return Err(ScorchError::new(
+                            ErrorKind::TypeMismatch,
+                            format!("Invalid type for variable {} (generated by a 'repeat' loop)", id),
+                        )
+                        .with_subject(id));
                     }
                     ctx.insert_variable(&id, Rc::new(RefCell::new(var)));
                 }
@@ -60,81 +99,82 @@ impl Interpreter {
         }
 
         let Some(_m_type) = self.type_checker.get(typename.as_str()) else {
-            panic!("{} isnt a type", typename)
+            return Err(ScorchError::new(ErrorKind::Internal, format!("{} isnt a type", typename)));
         };
 
         loop {
             let condition_result = match condition.as_ref() {
-                Some(expression) => {
-                    if let Value::Bool(val) = self.eval_deref(expression) {
-                        val
-                    } else {
-                        dbg!(expression);
-                        panic!("Expected boolean condition");
+                Some(expression) => match self.eval_deref(expression)? {
+                    Value::Bool(val) => val,
+                    other => {
+                        return Err(ScorchError::new(
+                            ErrorKind::TypeMismatch,
+                            format!("Expected boolean condition, got {:?}", other),
+                        ))
                     }
+                },
+                None => {
+                    return Err(ScorchError::new(
+                        ErrorKind::Internal,
+                        "Expected condition in conditional repeat statement",
+                    ))
                 }
-                None => panic!("Expected condition in conditional repeat statement"),
             };
-            
+
             if condition_result {
                 self.push_ctx();
                 let result = block.accept(self);
-                self.pop_ctx();
-                
+                self.pop_ctx()?;
+                let result = result?;
+
                 match &result {
                     Value::Int(..)
                     | Value::Double(..)
                     | Value::Bool(_)
                     | Value::Function(_)
-                    | Value::String(_) => return result,
+                    | Value::String(_) => return Ok(result),
                     Value::Return(value) => {
                         if let Some(val) = value.clone() {
-                            return *val;
+                            return Ok(*val);
                         } else {
-                            return result;
+                            return Ok(result);
                         }
                     }
                     _ => {}
                 }
             } else {
-                // pop iterator ctx.
                 let value = Value::Int(iter);
 
-                self.assign_var(id, &value);
+                self.assign_var(id, &value)?;
 
-                self.pop_ctx();
-                return Value::None();
+                return Ok(Value::None());
             }
 
             iter += 1;
 
             let value = Value::Int(iter);
 
-            self.assign_var(id, &value);
+            self.assign_var(id, &value)?;
         }
     }
     // this will seek parent contexts if & when the variable is not found in the current context.
     // this cannot be used to add new variables to a context.
-    pub fn assign_var<'ctx>(&mut self, id: &str, value: &'ctx Value) {
-        let Ok(_) = self
-            .context
+    pub fn assign_var<'ctx>(&mut self, id: &str, value: &'ctx Value) -> ScorchResult<()> {
+        self.context
             .borrow_mut()
             .seek_overwrite_in_parents(&id, &value)
-        else {
-            panic!("assignment error : {} not found.", id);
-        };
     }
-    pub fn visit_conditionless_repeat_stmnt(&mut self, block: &Box<Node>) -> Value {
+    pub fn visit_conditionless_repeat_stmnt(&mut self, block: &Box<Node>) -> ScorchResult {
         loop {
             self.push_ctx();
             let _result = block.accept(self);
-            self.pop_ctx();
-            match _result {
+            self.pop_ctx()?;
+            match _result? {
                 Value::Return(value) => {
                     if let Some(val) = value {
-                        return *val;
+                        return Ok(*val);
                     } else {
-                        return Value::None();
+                        return Ok(Value::None());
                     }
                 }
                 _ => {
@@ -143,29 +183,37 @@ impl Interpreter {
             }
         }
     }
-    
-    pub fn get_params_list(&mut self, param_nodes: &Vec<Node>) -> Vec<Parameter> {
+
+    pub fn get_params_list(&mut self, param_nodes: &Vec<Node>) -> ScorchResult<Vec<Parameter>> {
         let mut params = Vec::new();
         for param in param_nodes {
             if let Node::ParamDeclNode { varname, typename } = param {
                 let param_name = match varname.as_ref() {
                     Node::Identifier(id) => id.clone(),
                     _ => {
-                        dbg!(varname);
-                        panic!("Expected Identifier node");
+                        return Err(ScorchError::new(
+                            ErrorKind::Internal,
+                            format!("Expected Identifier node, got {:?}", varname),
+                        ))
                     }
                 };
 
                 let type_name = match typename.as_ref() {
                     Node::Identifier(id) => id.clone(),
                     _ => {
-                        dbg!(typename);
-                        panic!("Expected Identifier node");
+                        return Err(ScorchError::new(
+                            ErrorKind::Internal,
+                            format!("Expected Identifier node, got {:?}", typename),
+                        ))
                     }
                 };
 
                 let Some(m_type) = self.type_checker.get(type_name.as_str()) else {
-                    panic!("{} isnt a type", type_name)
+                    return Err(ScorchError::new(
+                        ErrorKind::UndefinedType,
+                        format!("{} isnt a type", type_name),
+                    )
+                    .with_subject(type_name));
                 };
 
                 let parameter = Parameter {
@@ -176,68 +224,114 @@ impl Interpreter {
                 params.push(parameter);
             }
         }
-        params
+        Ok(params)
     }
-    pub fn bin_op_float(&mut self, node: &Node, lhs: &f64, rhs: &f64) -> Value {
+    pub fn bin_op_float(&mut self, node: &Node, lhs: &f64, rhs: &f64) -> ScorchResult {
         let result: f64;
 
         let Node::BinaryOperation { lhs: _, op, rhs: _ } = node else {
-            dbg!(node);
-            panic!("Expected binary operation node");
+            return Err(ScorchError::new(ErrorKind::Internal, "Expected binary operation node"));
         };
 
+        if matches!(op, TokenKind::Divide | TokenKind::Modulo) && *rhs == 0.0 {
+            return Err(division_by_zero(lhs));
+        }
+
         match op {
             TokenKind::Add => result = lhs + rhs,
             TokenKind::Subtract => result = lhs - rhs,
             TokenKind::Multiply => result = lhs * rhs,
             TokenKind::Divide => result = lhs / rhs,
+            TokenKind::Modulo => result = lhs % rhs,
+            TokenKind::Exponent => result = lhs.powf(*rhs),
             _ => {
-                dbg!(node);
-                panic!("Expected binary operation node");
+                return Err(ScorchError::new(
+                    ErrorKind::InvalidOperator,
+                    format!("invalid binary operation {:?} on doubles", op),
+                ))
             }
         }
-        Value::Double(result)
+        Ok(Value::Double(result))
     }
-    pub fn bin_op_int(&mut self, node: &Node, lhs: &i32, rhs: &i32) -> Value {
-        let result: i32;
+    // every operation is checked, overflowing is a script error. see the wrapping_* builtins.
+    pub fn bin_op_int(&mut self, node: &Node, lhs: &i64, rhs: &i64) -> ScorchResult {
+        let result: Option<i64>;
         let Node::BinaryOperation { lhs: _, op, rhs: _ } = node else {
-            dbg!(node);
-            panic!("Expected binary operation node");
+            return Err(ScorchError::new(ErrorKind::Internal, "Expected binary operation node"));
         };
 
+        if matches!(op, TokenKind::Divide | TokenKind::Modulo) && *rhs == 0 {
+            return Err(division_by_zero(lhs));
+        }
+
         match op {
-            TokenKind::Add => result = lhs + rhs,
-            TokenKind::Subtract => result = lhs - rhs,
-            TokenKind::Multiply => result = lhs * rhs,
-            TokenKind::Divide => result = lhs / rhs,
+            TokenKind::Add => result = lhs.checked_add(*rhs),
+            TokenKind::Subtract => result = lhs.checked_sub(*rhs),
+            TokenKind::Multiply => result = lhs.checked_mul(*rhs),
+            TokenKind::Divide => result = lhs.checked_div(*rhs),
+            TokenKind::Modulo => result = lhs.checked_rem(*rhs),
+            TokenKind::Exponent => {
+                let Ok(exponent) = u32::try_from(*rhs) else {
+                    return Err(ScorchError::new(
+                        ErrorKind::InvalidOperator,
+                        format!("cannot raise an int to the negative power {}, use a double", rhs),
+                    ));
+                };
+                result = lhs.checked_pow(exponent)
+            }
+            TokenKind::BitwiseAnd => result = Some(lhs & rhs),
+            TokenKind::BitwiseOr => result = Some(lhs | rhs),
+            TokenKind::BitwiseXor => result = Some(lhs ^ rhs),
+            TokenKind::LeftShift | TokenKind::RightShift => {
+                let shifted = u32::try_from(*rhs).ok().and_then(|amount| match op {
+                    TokenKind::LeftShift => lhs.checked_shl(amount),
+                    _ => lhs.checked_shr(amount),
+                });
+                let Some(shifted) = shifted else {
+                    return Err(ScorchError::new(
+                        ErrorKind::InvalidOperator,
+                        format!("cannot shift by {}, expected 0 to {}", rhs, i64::BITS - 1),
+                    ));
+                };
+                result = Some(shifted)
+            }
             _ => {
-                dbg!(node);
-                panic!("Expected binary operation node");
+                return Err(ScorchError::new(
+                    ErrorKind::InvalidOperator,
+                    format!("invalid binary operation {:?} on ints", op),
+                ))
             }
         }
-        Value::Int(result)
+        match result {
+            Some(result) => Ok(Value::Int(result)),
+            None => Err(overflow(format!("{} {:?} {}", lhs, op, rhs))),
+        }
     }
-    pub fn bin_op_string(&mut self, node: &Node, lhs: &String, rhs: &String) -> Value {
+    pub fn bin_op_string(&mut self, node: &Node, lhs: &String, rhs: &String) -> ScorchResult {
         let Node::BinaryOperation { lhs: _, op, rhs: _ } = node else {
-            dbg!(node);
-            panic!("Expected binary operation node");
+            return Err(ScorchError::new(ErrorKind::Internal, "Expected binary operation node"));
         };
 
         let result: String;
         match op {
             TokenKind::Add => result = format!("{}{}", lhs, rhs),
             _ => {
-                dbg!(node);
-                panic!("invalid binary operation on strings");
+                return Err(ScorchError::new(
+                    ErrorKind::InvalidOperator,
+                    format!("invalid binary operation {:?} on strings", op),
+                ))
             }
         }
-        Value::String(result)
+        Ok(Value::String(result))
     }
-    
+
     pub fn new() -> Interpreter {
+        Interpreter::with_config(InterpreterConfig::default())
+    }
+    pub fn with_config(config: InterpreterConfig) -> Interpreter {
         let builtins = super::standard_functions::get_builtin_functions();
         let type_checker = TypeChecker::new();
-        let variables = HashMap::from([
+        let variables = IndexMap::from([
             ("none".to_string(), Rc::new(RefCell::new(Instance{
                 mutable: false,
                 value: Value::None(),
@@ -251,57 +345,214 @@ impl Interpreter {
             })),
             builtin: builtins,
             type_checker: TypeChecker::new(),
+            config,
+            stdout: Box::new(io::stdout()),
+            stderr: Box::new(io::stderr()),
+            stdin: Box::new(BufReader::new(io::stdin())),
+            script_args: Vec::new(),
+            steps: 0,
+            call_stack: Vec::new(),
+            deadline: None,
+            spans: Spans::new(),
+            body_spans: Vec::new(),
+            assoc_scopes: Vec::new(),
         }
     }
-    pub fn try_find_and_execute_fn(&mut self, arguments: &Option<Vec<Node>>, id: &String) -> Value {
-        let args = Function::extract_args(self, arguments);
-        
-        let function: Option<Rc<Function>>;
-        
-        {
-            let mut ctx = self.context.borrow_mut();
-            // function pointer
-            let Some(fn_ptr) = ctx.find_variable(id) else {
-                let Some(builtin) = self.builtin.get_mut(id) else {
-                    dbg!(id);
-                    panic!("Function {id}  not found");
-                };
-                return builtin.call(&mut ctx, &self.type_checker, args);
-            };
-            
-            function = match &fn_ptr.borrow_mut().value {
-                Value::Function(func) => Some(func.clone()),
-                _ => panic!("Expected function"),
-            };
+    // restarts the step budget and the timeout, hosts calling into a script
+    // repeatedly (once per frame, etc) should call this before each call.
+    pub fn reset_limits(&mut self) {
+        self.steps = 0;
+        self.deadline = None;
+    }
+    // called once per visited node, so loops, calls and expressions are all counted.
+    fn step(&mut self) -> ScorchResult<()> {
+        self.steps += 1;
+
+        if let Some(max_steps) = self.config.max_steps {
+            if self.steps > max_steps {
+                return Err(ScorchError::new(
+                    ErrorKind::StepLimitExceeded,
+                    format!("execution exceeded the limit of {} steps", max_steps),
+                ));
+            }
+        }
+
+        if let Some(timeout) = self.config.timeout {
+            let deadline = *self.deadline.get_or_insert_with(|| Instant::now() + timeout);
+            // reading the clock is comparatively slow, so only check it every so often.
+            if self.steps % 256 == 0 && Instant::now() > deadline {
+                return Err(ScorchError::new(
+                    ErrorKind::Timeout,
+                    format!("execution exceeded the timeout of {:?}", timeout),
+                ));
+            }
         }
-        
-        let Some(function) = function else {
-            dbg!(id);
-            panic!("Function {id} not found");
+        Ok(())
+    }
+    // exposes a rust function to scorch code as a builtin, arguments and the return value are converted
+    // with FromValue & IntoValue, and arity / type mismatches are reported as errors.
+    pub fn register_fn<Args, F>(&mut self, name: &str, func: F)
+    where
+        F: NativeFn<Args> + 'static,
+    {
+        let fn_name = name.to_string();
+        let params = func.param_types();
+        // builtins are shared & called through `Fn`, the cell lets closures that keep state still mutate it.
+        let func = RefCell::new(func);
+        self.builtin.insert(
+            fn_name.clone(),
+            StandardFunction::new(Box::new(move |_interpreter, args| {
+                func.borrow_mut().invoke(&fn_name, args)
+            }))
+            .signature(&params, DYNAMIC_TNAME),
+        );
+    }
+    pub fn try_find_and_execute_fn(&mut self, arguments: &Option<Vec<Node>>, id: &String) -> ScorchResult {
+        let args = Function::extract_args(self, arguments)?;
+        self.call(id, args)
+    }
+    // calls a scorch function or builtin by name with already evaluated arguments,
+    // this is how hosts drive functions defined by a loaded script.
+    pub fn call(&mut self, id: &str, args: Vec<Value>) -> ScorchResult {
+        // function pointer
+        let fn_ptr = self.context.borrow().find_variable(id);
+
+        let Some(fn_ptr) = fn_ptr else {
+            let Some(builtin) = self.builtin.get(id) else {
+                return Err(ScorchError::new(
+                    ErrorKind::UndefinedFunction,
+                    format!("Function {id} not found"),
+                )
+                .with_subject(id));
+            };
+            let builtin = builtin.clone();
+            if let Some(capability) = builtin.capability {
+                if !self.config.allows(capability) {
+                    return Err(ScorchError::new(
+                        ErrorKind::PermissionDenied,
+                        format!("{id} requires the {:?} capability, which this interpreter doesn't allow", capability),
+                    )
+                    .with_subject(id));
+                }
+            }
+            return builtin.call(self, args);
         };
-        
-        // valid parameterless
-        if function.params.len() + args.len() == 0 {
-            
-            let result = function.body.accept(self);
-            
-            match result {
-                Value::Return(Some(return_value)) => return *return_value,
-                Value::Return(None) => return Value::None(),
-                _ => return result,
+
+        let callee = fn_ptr.borrow().value.clone();
+        self.call_value(callee, args)
+    }
+    // calls a function value, like one held by a variable or a field,
+    // binding the arguments the same way a call by name does.
+    // TODO: `handlers[2](x)` and `make_adder(1)(2)` should evaluate their callee and call it through
+    // here, but Node::FunctionCall only carries a name. that needs a call node with an expression
+    // callee in scorch-parser, and a bump of the pinned rev, before the visitor can handle it.
+    pub fn call_value(&mut self, callee: Value, args: Vec<Value>) -> ScorchResult {
+        match callee.dereference() {
+            Value::Function(function) => self.call_function(&function, args),
+            other => Err(ScorchError::new(
+                ErrorKind::TypeMismatch,
+                format!("{} is not callable ({})", get_typename(&other), other),
+            )),
+        }
+    }
+    pub fn get_global(&self, id: &str) -> Option<Value> {
+        let var = self.context.borrow().find_variable(id)?;
+        let value = var.borrow().value.clone();
+        Some(value)
+    }
+    // overwrites an existing variable, validating it against the variable's type,
+    // or declares a new mutable one typed after the value.
+    pub fn set_global(&mut self, id: &str, value: Value) -> ScorchResult<()> {
+        let existing = self.context.borrow().find_variable(id);
+
+        match existing {
+            Some(var) => {
+                let mut var = var.borrow_mut();
+                if !var.m_type.borrow().validate(&value) {
+                    return Err(ScorchError::new(
+                        ErrorKind::TypeMismatch,
+                        format!("cannot assign {:?} to {id} : {}", value, var.m_type.borrow().name),
+                    )
+                    .with_subject(id));
+                }
+                var.set_value(&value);
+            }
+            None => {
+                let Some(m_type) = self.type_checker.from_value(&value) else {
+                    return Err(ScorchError::new(
+                        ErrorKind::UndefinedType,
+                        format!("{:?} doesn't match to a valid type", value),
+                    )
+                    .with_subject(id));
+                };
+                self.context.borrow_mut().insert_variable(
+                    id,
+                    Rc::new(RefCell::new(Instance::new(true, value, m_type))),
+                );
+            }
+        }
+        Ok(())
+    }
+    pub fn call_stack(&self) -> &[String] {
+        &self.call_stack
+    }
+    // binds already evaluated arguments to the function's parameters and runs its body.
+    pub fn call_function(&mut self, function: &Rc<Function>, args: Vec<Value>) -> ScorchResult {
+        self.call_frame(function.name.clone(), function, args)
+    }
+    // like call_function, `name` is what shows up in tracebacks.
+    fn call_frame(&mut self, name: String, function: &Rc<Function>, args: Vec<Value>) -> ScorchResult {
+        if let Some(max_call_depth) = self.config.max_call_depth {
+            if self.call_stack.len() >= max_call_depth {
+                return Err(ScorchError::new(
+                    ErrorKind::CallDepthExceeded,
+                    format!("exceeded the maximum call depth of {}", max_call_depth),
+                )
+                .with_subject(&function.name));
             }
-            
         }
-        
+
+        self.call_stack.push(name);
+        let result = self.bind_args_and_execute(function, args);
+        self.call_stack.pop();
+        result
+    }
+    // called when an error unwinds out of the body of the innermost function.
+    fn leave_frame(&self, err: ScorchError) -> ScorchError {
+        match self.call_stack.last() {
+            Some(name) => err.leave_frame(name),
+            None => err,
+        }
+    }
+    fn bind_args_and_execute(&mut self, function: &Rc<Function>, args: Vec<Value>) -> ScorchResult {
         if args.len() != function.params.len() {
-            panic!("Number of arguments does not match the number of parameters :: expected {}, got {}", function.params.len(), args.len());
+            return Err(ScorchError::new(
+                ErrorKind::ArgumentCount,
+                format!("Number of arguments does not match the number of parameters :: expected {}, got {}", function.params.len(), args.len()),
+            )
+            .with_subject(&function.name));
         }
-        
-        self.push_ctx();
-        
+
+        let Some(closure) = function.closure.scope() else {
+            return Err(ScorchError::new(
+                ErrorKind::Internal,
+                format!("the scope {} was declared in no longer exists", function.name),
+            )
+            .with_subject(&function.name));
+        };
+
+        // lexical scoping, the body sees its parameters & whatever was in scope where it was declared.
+        let caller = std::mem::replace(&mut self.context, Context::new());
+        self.context.borrow_mut().parent = Some(closure);
+
         for (arg, param) in args.iter().zip(function.params.iter()) {
             if !param.m_type.borrow().validate(arg) {
-                panic!("Argument type does not match parameter type.\n provided argument: {:?} expected parameter : {:?}", arg, param)
+                self.context = caller;
+                return Err(ScorchError::new(
+                    ErrorKind::TypeMismatch,
+                    format!("Argument type does not match parameter type.\n provided argument: {:?} expected parameter : {:?}", arg, param),
+                )
+                .with_subject(&param.name));
             } else {
                 self.context.borrow_mut().insert_variable(
                     &param.name,
@@ -313,70 +564,117 @@ impl Interpreter {
                 );
             }
         }
-        
-        let ret = function.body.accept(self);
 
-        self.pop_ctx();
+        self.body_spans.push(Rc::clone(&function.spans));
+        let ret = function.body.accept(self).map_err(|err| self.leave_frame(err));
+        self.body_spans.pop();
 
-        if let Value::Return(Some(return_value)) = ret {
-            return *return_value;
+        let frame = std::mem::replace(&mut self.context, caller);
+        Context::release(&frame);
+
+        if let Value::Return(Some(return_value)) = ret? {
+            return self.check_return(function, *return_value);
         }
 
-        Value::None()
+        // falling off the end returns none.
+        self.check_return(function, Value::None())
+    }
+    fn check_return(&self, function: &Rc<Function>, value: Value) -> ScorchResult {
+        let return_type = function.return_type.borrow();
+        let returned = value.clone().dereference();
+
+        if !return_type.validate(&returned) {
+            return Err(ScorchError::new(
+                ErrorKind::TypeMismatch,
+                format!(
+                    "{} should return {}, but returned {} ({})",
+                    function.name,
+                    return_type.name,
+                    get_typename(&returned),
+                    returned
+                ),
+            )
+            .with_subject(&function.name));
+        }
+        Ok(value)
     }
 
-    pub fn dot_op(&mut self, lhs: &Box<Node>, rhs: &Box<Node>) -> Value {
-        let lhs_value = self.eval_deref(lhs);
-        
+    pub fn dot_op(&mut self, lhs: &Box<Node>, rhs: &Box<Node>) -> ScorchResult {
+        let lhs_value = self.eval_deref(lhs)?;
+
         match rhs.as_ref() {
             Node::Identifier(id) => match lhs_value {
                 Value::StructInstance {
                     typename,
                     context,
+                    ..
                 } => {
                     let Some(var) = context.find_variable(id) else {
-                        panic!("unable to find variable {id} in struct {typename}");
+                        return Err(ScorchError::new(
+                            ErrorKind::UndefinedVariable,
+                            format!("unable to find variable {id} in struct {typename}"),
+                        )
+                        .with_subject(id));
                     };
-                    return Value::Reference(var);
+                    return Ok(Value::Reference(var));
                 }
-                _ => {
-                    panic!("expected struct");
+                other => {
+                    return Err(ScorchError::new(
+                        ErrorKind::TypeMismatch,
+                        format!("expected struct, got {:?}", other),
+                    )
+                    .with_subject(id));
                 }
             },
             Node::FunctionCall { id, arguments } => {
-                
+                // the value on the left is passed as the first argument, it's only evaluated once.
+                let this = lhs_value.clone();
                 match lhs_value {
-                    Value::StructInstance { typename, context } => {
-                        if let Some(mut args) = arguments.clone() {
-                            args.insert(0, *lhs.clone());
-                            self.try_call_associated_fn(Some(args), id, typename, context)
-                        } else {
-                            let mut args = Vec::new();
-                            args.push(*lhs.clone());
-                            self.try_call_associated_fn(Some(args), id, typename, context)
-                        }
+                    // a field holding a function is called as is, the struct isn't passed to it.
+                    Value::StructInstance { typename, context, fields } if fields.contains(id) => {
+                        let Some(field) = context.find_variable(id) else {
+                            return Err(ScorchError::new(
+                                ErrorKind::UndefinedVariable,
+                                format!("unable to find variable {id} in struct {typename}"),
+                            )
+                            .with_subject(id));
+                        };
+                        let callee = field.borrow().value.clone();
+                        let args = Function::extract_args(self, arguments)?;
+                        self.call_value(callee, args)
+                    }
+                    Value::StructInstance { typename, context, .. } => {
+                        let mut args = vec![this];
+                        args.extend(Function::extract_args(self, arguments)?);
+                        self.try_call_associated_fn(args, id, typename, context)
                     },
                     _ => {
-                        if let Some(mut args) = arguments.clone() {
-                            args.insert(0, *lhs.clone());
-                            self.try_find_and_execute_fn(&Some(args), id)
-                        } else {
-                            let mut args = Vec::new();
-                            args.push(*lhs.clone());
-                            self.try_find_and_execute_fn(&Some(args), id)
-                        }
+                        let mut args = vec![this];
+                        args.extend(Function::extract_args(self, arguments)?);
+                        self.call(id, args)
                     }
                 }
-                
-                
-                
+
+
+
             }
             _ => {
-                dbg!(lhs, rhs);
-                panic!("Unexpected node type");
+                Err(ScorchError::new(
+                    ErrorKind::Internal,
+                    format!("Unexpected node type on the right of a dot : {:?}", rhs),
+                ))
             }
         }
     }
+    // how something declared in the current scope holds on to it. the top level scope belongs to
+    // the interpreter, so it's held weakly, its functions & types don't need to keep it alive.
+    fn capture(&self) -> Closure {
+        if self.context.borrow().parent.is_none() {
+            Closure::Weak(Rc::downgrade(&self.context))
+        } else {
+            Closure::Strong(Rc::clone(&self.context))
+        }
+    }
     pub fn push_ctx(&mut self) {
         let current = self.context.clone();
 
@@ -384,83 +682,201 @@ impl Interpreter {
 
         self.context.borrow_mut().parent = Some(Rc::clone(&current));
     }
-    pub fn pop_ctx(&mut self) {
+    pub fn pop_ctx(&mut self) -> ScorchResult<()> {
         let current = self.context.clone();
-        
-        self.context = match current.borrow_mut().parent.take() {
-            Some(parent) => Rc::clone(&parent),
-            None => panic!("Cannot pop root context"),
+
+        // the parent is kept, functions declared in this scope still reach outer scopes through it.
+        let parent = current.borrow().parent.clone();
+        let Some(parent) = parent else {
+            return Err(ScorchError::new(ErrorKind::Internal, "cannot pop the root context"));
         };
+        Context::release(&current);
+        self.context = parent;
+        Ok(())
     }
 
-    pub fn access_array(&self, id: &str, index: usize) -> Value {
+    pub fn access_array(&self, id: &str, index: usize) -> ScorchResult {
         let ctx = self.context.borrow();
-        let var = ctx.find_variable(id).expect("Variable not found");
+        let Some(var) = ctx.find_variable(id) else {
+            return Err(ScorchError::new(
+                ErrorKind::UndefinedVariable,
+                format!("Variable {id} not found"),
+            )
+            .with_subject(id));
+        };
         let var = var.borrow();
 
         match &var.value {
             Value::Array(_, elements) => {
                 let elements = elements.borrow();
                 if elements.len() <= index {
-                    panic!("Array index out of bounds :: {}[{}]", id, index);
+                    return Err(ScorchError::new(
+                        ErrorKind::IndexOutOfBounds,
+                        format!("Array index out of bounds :: {}[{}]", id, index),
+                    )
+                    .with_subject(id));
                 }
-                Value::Reference(Rc::clone(&elements[index]))
+                Ok(Value::Reference(Rc::clone(&elements[index])))
             }
-            _ => panic!("Expected Array node"),
+            other => Err(ScorchError::new(
+                ErrorKind::TypeMismatch,
+                format!("Expected array, got {:?}", other),
+            )
+            .with_subject(id)),
         }
     }
 
-    pub fn assign_to_array(&mut self, id: &str, index: usize, value: Value) {
+    pub fn assign_to_array(&mut self, id: &str, index: usize, value: Value) -> ScorchResult<()> {
         let ctx = self.context.borrow();
-        let var = ctx.find_variable(id).expect("Variable not found");
+        let Some(var) = ctx.find_variable(id) else {
+            return Err(ScorchError::new(
+                ErrorKind::UndefinedVariable,
+                format!("Variable {id} not found"),
+            )
+            .with_subject(id));
+        };
         let mut var = var.borrow_mut();
 
         match &mut var.value {
             Value::Array(mutable, elements) => {
                 if !*mutable {
-                    panic!("Cannot mutate immutable array");
+                    return Err(ScorchError::new(
+                        ErrorKind::Immutable,
+                        "Cannot mutate immutable array",
+                    )
+                    .with_subject(id));
                 }
                 let mut elements = elements.borrow_mut();
                 if elements.len() <= index {
-                    panic!("Array index out of bounds :: {}[{}]", id, index);
+                    return Err(ScorchError::new(
+                        ErrorKind::IndexOutOfBounds,
+                        format!("Array index out of bounds :: {}[{}]", id, index),
+                    )
+                    .with_subject(id));
                 }
-                {
-                    elements[index].borrow_mut().set_value(&value);
-                }
-                
-                let element = &elements[index].borrow();
-                if !TypeChecker::validate(element) {
-                    dbg!(&elements[index]);
-                    panic!("Invalid type");
+                let mut element = elements[index].borrow_mut();
+                if !element.m_type.borrow().validate(&value) {
+                    return Err(ScorchError::new(
+                        ErrorKind::TypeMismatch,
+                        format!("Invalid type for array element : {:?}", value),
+                    )
+                    .with_subject(id));
                 }
+                element.set_value(&value);
+                Ok(())
             }
-            _ => panic!("Expected Array node"),
+            other => Err(ScorchError::new(
+                ErrorKind::TypeMismatch,
+                format!("Expected array, got {:?}", other),
+            )
+            .with_subject(id)),
         }
     }
 
-    fn evaluate_expression(&mut self, lhs: &Box<Node>, rhs: &Box<Node>, op: &TokenKind) -> Value {
-        let lhs_value = self.eval_deref(lhs);
-        let rhs_value = self.eval_deref(rhs);
-        
-        let l_type = self.type_checker.from_value(&lhs_value);
-        let r_type = self.type_checker.from_value(&rhs_value);
-        
+    // looks up an operator overload for already evaluated operands.
+    fn apply_operator(&mut self, lhs_value: &Value, rhs_value: &Value, op: &TokenKind) -> ScorchResult {
+        let l_type = self.type_checker.from_value(lhs_value);
+        let r_type = self.type_checker.from_value(rhs_value);
+
         let l_type = match l_type {
             Some(t) => Rc::clone(&t),
-            None => panic!("invalid type in relational expression : {:?}", lhs_value),
+            None => {
+                return Err(ScorchError::new(
+                    ErrorKind::UndefinedType,
+                    format!("invalid type in relational expression : {:?}", lhs_value),
+                ))
+            }
         };
         let r_type = match r_type {
             Some(t) => t,
-            None => panic!("invalid type in relational expression : {:?}", rhs_value),
+            None => {
+                return Err(ScorchError::new(
+                    ErrorKind::UndefinedType,
+                    format!("invalid type in relational expression : {:?}", rhs_value),
+                ))
+            }
         };
-        
-        let result = l_type.borrow().perform_bin_op(op, &r_type, &lhs_value, &rhs_value);
-        result
+
+        let method = l_type.borrow().overload_for(op, &r_type)?;
+        method(self, lhs_value, rhs_value)
+    }
+
+    // associated functions named after an operator (see overloaded_operator) become its overload,
+    // `this` is the lhs and the type of the second parameter decides which rhs it applies to.
+    // a method with the wrong number of parameters is reported at its declaration in `block`,
+    // after the others are registered.
+    fn register_operators(&mut self, typename: &str, block: &Node) -> ScorchResult<()> {
+        let Some(type_) = self.type_checker.get(typename) else {
+            return Ok(());
+        };
+        let functions: Vec<Rc<Function>> = type_
+            .borrow()
+            .context
+            .variables
+            .values()
+            .filter_map(|var| match &var.borrow().value {
+                Value::Function(function) => Some(Rc::clone(function)),
+                _ => None,
+            })
+            .collect();
+
+        let mut bad_arity = None;
+        for function in functions {
+            let Some(op) = overloaded_operator(&function.name) else {
+                continue;
+            };
+            if function.params.len() != 2 {
+                let declaration = match block {
+                    Node::Block(statements) => statements
+                        .iter()
+                        .find(|statement| matches!(statement.as_ref(), Node::FnDeclStmnt { id, .. } if *id == function.name)),
+                    _ => None,
+                };
+                let err = ScorchError::new(
+                    ErrorKind::ArgumentCount,
+                    format!(
+                        "operator {} should take 2 parameters (this : {}, rhs), got {}",
+                        function.name,
+                        typename,
+                        function.params.len()
+                    ),
+                )
+                .with_subject(&function.name)
+                .at(declaration.and_then(|declaration| self.location(declaration)));
+                bad_arity = bad_arity.or(Some(err));
+                continue;
+            }
+            let rhs_t = function.params[1].m_type.borrow().name.clone();
+            let name = format!("{}.{}", typename, function.name);
+
+            type_.borrow_mut().add_operator(OperatorOverload {
+                rhs_t,
+                op,
+                method: Rc::new(move |interpreter, lhs, rhs| {
+                    interpreter.call_frame(name.clone(), &function, vec![lhs.clone(), rhs.clone()])
+                }),
+            });
+        }
+        match bad_arity {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+    fn has_operator(&self, lhs_value: &Value, rhs_value: &Value, op: &TokenKind) -> bool {
+        let (Some(l_type), Some(r_type)) = (
+            self.type_checker.from_value(lhs_value),
+            self.type_checker.from_value(rhs_value),
+        ) else {
+            return false;
+        };
+        let rhs_typename = r_type.borrow().name.clone();
+        let found = l_type.borrow().find_operator(op, &rhs_typename).is_some();
+        found
     }
-    
-    pub fn eval_deref(&mut self, expression: &Node) -> Value {
-        let value = expression.accept(self);
-                
+
+    pub fn eval_deref(&mut self, expression: &Node) -> ScorchResult {
+        let value = expression.accept(self)?;
+
         let result = match &value {
             Value::Reference(inner) => {
                 let inner = inner.borrow();
@@ -468,173 +884,230 @@ impl Interpreter {
             }
             _ => value,
         };
-        result
+        Ok(result)
     }
-    
-    fn try_call_associated_fn(&mut self, arguments: Option<Vec<Node>>, id: &str, typename: String, context: Box<Context>) -> Value {
-        let func = context.find_variable(id);
-        
-        let Some(func) = func else {
-            panic!("unable to find function {id} in struct {typename}");
-        };
-        
-        let Value::Function(function) = func.borrow().value.clone() else {
-            panic!("expected function");
-        };
-        
-        let args = Function::extract_args(self, &arguments);
-        
-        // valid parameterless
-        if function.params.len() + args.len() == 0 {
-            
-            let result = function.body.accept(self);
-            
-            match result {
-                Value::Return(Some(return_value)) => return *return_value,
-                Value::Return(None) => return Value::None(),
-                _ => return result,
-            }
-            
-        }
-        
-        if args.len() != function.params.len() {
-            panic!("Number of arguments does not match the number of parameters :: expected {}, got {}", function.params.len(), args.len());
-        }
-        
-        self.push_ctx();
-        
-        for (arg, param) in args.iter().zip(function.params.iter()) {
-            if !param.m_type.borrow().validate(arg) {
-                panic!("Argument type does not match parameter type.\n provided argument: {:#?} expected parameter : {:#?}", arg, param)
-            } else {
-                self.context.borrow_mut().insert_variable(
-                    &param.name,
-                    Rc::new(RefCell::new(Instance::new(
-                        false,
-                        arg.clone(),
-                        Rc::clone(&param.m_type),
-                    ))),
-                );
-            }
-        }
-        
-        let ret = function.body.accept(self);
 
-        self.pop_ctx();
+    // the names of a struct's fields, checking each is a declaration of a known type.
+    fn struct_fields(&self, statements: &Vec<Box<Node>>) -> ScorchResult<Vec<String>> {
+        let mut fields = Vec::new();
 
-        if let Value::Return(Some(return_value)) = ret {
-            return *return_value;
-        }
+        for statement in statements {
+            let Node::DeclStmt {
+                target_type, id, ..
+            } = statement.as_ref()
+            else {
+                return Err(ScorchError::new(
+                    ErrorKind::Internal,
+                    format!("Expected declaration, got {:#?}", statement),
+                ));
+            };
+
+            if self.type_checker.get(&target_type).is_none() {
+                return Err(ScorchError::new(
+                    ErrorKind::UndefinedType,
+                    format!("{} not a valid type", target_type),
+                )
+                .with_subject(target_type));
+            };
 
-        Value::None()
-        
+            fields.push(id.clone());
+        }
+        Ok(fields)
     }
-}
-impl Visitor<Value> for Interpreter {
-    // top level nodes
-    fn visit_program(&mut self, node: &Node) -> Value {
-        let statements = match node {
-            Node::Program(statements) => statements,
-            _ => panic!("expected program node"),
-        };
+    // evaluates a struct's field defaults for a new instance. like a function body they see the
+    // scope the struct was declared in, and the fields before them. a name in that scope doesn't
+    // clash with a field, only two fields with the same name do.
+    fn instance_fields(&mut self, field_decls: &FieldDecls, scope: Rc<RefCell<Context>>) -> ScorchResult<IndexMap<String, Rc<RefCell<Instance>>>> {
+        let fields = Context::new();
+        fields.borrow_mut().parent = Some(scope);
 
-        for stmnt in statements {
-            let result = stmnt.accept(self);
-            match result {
-                Value::Return(Some(return_value)) => return *return_value,
-                Value::Return(None) => return Value::None(),
-                _ => continue,
+        let caller = std::mem::replace(&mut self.context, Rc::clone(&fields));
+        self.body_spans.push(Rc::clone(&field_decls.spans));
+        let mut result = Ok(Value::None());
+        for statement in &field_decls.statements {
+            result = self.instance_field(statement);
+            result = self.at(statement, result);
+            if result.is_err() {
+                break;
             }
         }
+        self.body_spans.pop();
+        self.context = caller;
+        result?;
 
-        Value::None()
+        let variables = std::mem::take(&mut fields.borrow_mut().variables);
+        Ok(variables)
     }
-    fn visit_block(&mut self, node: &Node) -> Value {
-        let statements = match node {
-            Node::Block(statements) => statements,
-            _ => panic!("Expected Block node"),
+    fn instance_field(&mut self, statement: &Node) -> ScorchResult {
+        self.step()?;
+        let Node::DeclStmt {
+            target_type,
+            id,
+            expression,
+            mutable,
+        } = statement
+        else {
+            return Err(ScorchError::new(ErrorKind::Internal, "Expected Declaration node"));
         };
-        
-        for statement in statements {
-            let value = statement.accept(self);
-            match value {
-                Value::Return(..) => return value,
-                _ => continue,
-            }
+        let Some(m_type) = self.type_checker.get(target_type) else {
+            return Err(ScorchError::new(
+                ErrorKind::UndefinedType,
+                format!("{} not a valid type", target_type),
+            )
+            .with_subject(target_type));
+        };
+
+        let value = self.eval_deref(expression)?;
+        let var = Instance::new(*mutable, value, m_type);
+        if !TypeChecker::validate(&var) {
+            return Err(ScorchError::new(
+                ErrorKind::TypeMismatch,
+                format!("invalid type in declaration '{id} : {}', recieved value : {:?}", var.m_type.borrow().name, var.value),
+            )
+            .with_subject(id));
         }
 
-        Value::None()
+        let mut ctx = self.context.borrow_mut();
+        if ctx.variables.contains_key(id) {
+            return Err(ScorchError::new(
+                ErrorKind::Redefinition,
+                format!("field {id} is declared more than once"),
+            )
+            .with_subject(id));
+        }
+        ctx.insert_variable(id, Rc::new(RefCell::new(var)));
+        Ok(Value::None())
     }
 
-    // statements
-    fn visit_if_stmnt(&mut self, node: &Node) -> Value {
-        let (condition, true_block, else_block) = match node {
-            Node::IfStmnt {
-                condition,
-                block: true_block,
-                else_stmnt: else_block,
-            } => (condition, true_block, else_block),
-            _ => panic!("Expected WhereStmnt node"),
+    // applies the arguments of `new Type(..)` to a fresh instance. positional arguments fill the
+    // fields in declaration order and named ones (`field = value`) fill that field, unless the type
+    // has an init function, then the positional arguments are passed to it after `this`.
+    fn construct(&mut self, typename: &str, instance: &Value, fields: &[String], arguments: &Option<Vec<Node>>) -> ScorchResult<()> {
+        let Value::StructInstance { context, .. } = instance else {
+            return Ok(());
         };
-
-        let condition_result = match condition.accept(self) {
-            Value::Bool(condition_result) => condition_result,
-            _ => panic!("Expected boolean condition"),
+        let init = match context.variables.get("init").map(|var| var.borrow().value.clone()) {
+            Some(Value::Function(init)) => Some(init),
+            _ => None,
         };
 
-        if condition_result {
-            self.push_ctx();
-            let returned = true_block.accept(self);
-            self.pop_ctx();
-            match returned {
-                Value::Return(_) => return returned,
-                _ => {}
+        let mut assignments: Vec<(String, Value)> = Vec::new();
+        let mut positional = Vec::new();
+        for arg in arguments.iter().flatten() {
+            match named_argument(arg) {
+                Some((name, expression)) => {
+                    let value = self.eval_deref(expression)?;
+                    assignments.push((name.clone(), value));
+                }
+                None => positional.push(self.eval_deref(arg)?),
             }
-        } else if let Some(else_stmnt) = else_block {
-            let returned = else_stmnt.accept(self);
-            match returned {
-                Value::Return(_) => return returned,
-                _ => {}
+        }
+
+        if init.is_none() {
+            if positional.len() > fields.len() {
+                return Err(ScorchError::new(
+                    ErrorKind::ArgumentCount,
+                    format!("{} has {} fields, got {} arguments", typename, fields.len(), positional.len()),
+                )
+                .with_subject(typename));
+            }
+            let named = std::mem::take(&mut assignments);
+            assignments = fields.iter().cloned().zip(positional.drain(..)).collect();
+            assignments.extend(named);
+        }
+
+        for (i, (name, value)) in assignments.iter().enumerate() {
+            if assignments[..i].iter().any(|(other, _)| other == name) {
+                return Err(ScorchError::new(
+                    ErrorKind::Redefinition,
+                    format!("{}.{} is initialized more than once", typename, name),
+                )
+                .with_subject(name));
+            }
+            let field = context.variables.get(name).filter(|_| fields.contains(name));
+            let Some(field) = field else {
+                return Err(ScorchError::new(
+                    ErrorKind::UndefinedVariable,
+                    format!("{} has no field {}", typename, name),
+                )
+                .with_subject(name));
+            };
+            // fields are initialized, not assigned, so immutable ones can be set too.
+            let mut field = field.borrow_mut();
+            if !field.m_type.borrow().validate(value) {
+                return Err(ScorchError::new(
+                    ErrorKind::TypeMismatch,
+                    format!(
+                        "{}.{} is {}, got {} ({})",
+                        typename,
+                        name,
+                        field.m_type.borrow().name,
+                        get_typename(value),
+                        value
+                    ),
+                )
+                .with_subject(name));
             }
+            field.set_value(value);
         }
 
-        Value::None()
+        if let Some(init) = init {
+            let mut args = vec![instance.clone()];
+            args.extend(positional);
+            self.call_frame(format!("{typename}.init"), &init, args)?;
+        }
+        Ok(())
     }
-    fn visit_else_stmnt(&mut self, node: &Node) -> Value {
-        let (condition, true_block, else_stmnt) = match node {
-            Node::ElseStmnt {
-                condition,
-                block: true_block,
-                else_stmnt,
-            } => (condition, true_block, else_stmnt),
-            _ => panic!("Expected OrStmnt node"),
-        };
 
-        let condition_result = match condition.as_ref() {
-            Some(expression) => match expression.accept(self) {
-                Value::Bool(val) => val,
-                _ => panic!("Expected boolean condition"),
-            },
-            None => true,
+    fn try_call_associated_fn(&mut self, args: Vec<Value>, id: &str, typename: String, context: Box<Context>) -> ScorchResult {
+        // anything that isn't an associated function is called like it would be on any other value.
+        let Some(func) = context.variables.get(id).cloned() else {
+            return self.call(id, args);
         };
 
-        if condition_result {
-            self.push_ctx();
-            let returned = true_block.accept(self);
-            self.pop_ctx();
-
-            match returned {
-                Value::Return(_) => return returned,
-                _ => {}
-            }
+        let Value::Function(function) = func.borrow().value.clone() else {
+            return Err(ScorchError::new(
+                ErrorKind::TypeMismatch,
+                format!("expected function, {typename}.{id} is not callable"),
+            )
+            .with_subject(id));
+        };
 
-        } else if let Some(else_statement) = else_stmnt {
-            else_statement.accept(self);
+        self.call_frame(format!("{typename}.{id}"), &function, args)
+    }
+}
+fn overflow(expression: String) -> ScorchError {
+    ScorchError::new(ErrorKind::Overflow, format!("integer overflow : {}", expression))
+}
+fn division_by_zero(lhs: impl std::fmt::Display) -> ScorchError {
+    ScorchError::new(ErrorKind::DivisionByZero, format!("cannot divide {} by zero", lhs))
+}
+// `name = value` in the arguments of `new Type(..)` initializes a field by name.
+pub fn named_argument(arg: &Node) -> Option<(&String, &Node)> {
+    let Node::AssignStmnt { id, expression } = arg else {
+        return None;
+    };
+    let Node::Identifier(name) = id.as_ref() else {
+        return None;
+    };
+    Some((name, expression.as_ref()))
+}
+// the visitor methods that can fail on their own, the visitor wraps them so errors point at the node.
+impl Interpreter {
+    fn at(&self, node: &Node, result: ScorchResult) -> ScorchResult {
+        result.map_err(|err| err.at(self.location(node)))
+    }
+    fn current_spans(&self) -> &Spans {
+        match self.body_spans.last() {
+            Some(spans) => spans,
+            None => &self.spans,
         }
-
-        Value::None()
     }
-    fn visit_declaration(&mut self, node: &Node) -> Value {
+    fn location(&self, node: &Node) -> Option<&SourceLocation> {
+        self.current_spans().location(node)
+    }
+    fn declaration(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
         if let Node::DeclStmt {
             target_type,
             id,
@@ -645,30 +1118,38 @@ impl Visitor<Value> for Interpreter {
             let value: Value;
             let var: Instance;
             let mutability = *mutable;
-            
+
             match self.type_checker.get(target_type.as_str()) {
                 Some(m_type) => {
-                    let deref = self.eval_deref(expression);
+                    let deref = self.eval_deref(expression)?;
                     value = deref;
                     var = Instance::new(mutability, value, m_type);
-                    
+
                     if !TypeChecker::validate(&var) {
-                        println!("recieved value: ");
-                        dbg!(&var.value);
-                        panic!("invalid type in declaration '{id} : {}'", var.m_type.borrow().name);
+                        return Err(ScorchError::new(
+                            ErrorKind::TypeMismatch,
+                            format!("invalid type in declaration '{id} : {}', recieved value : {:?}", var.m_type.borrow().name, var.value),
+                        )
+                        .with_subject(id));
                     }
                 }
                 _ => {
-                    dbg!(node);
-                    panic!("{} is not a supported or known type.", target_type);
+                    return Err(ScorchError::new(
+                        ErrorKind::UndefinedType,
+                        format!("{} is not a supported or known type.", target_type),
+                    )
+                    .with_subject(target_type));
                 }
             }
             {
                 let mut ctx = self.context.borrow_mut();
                 match ctx.find_variable(&id) {
                     Some(_) => {
-                        dbg!(node);
-                        panic!("redefinition of variable {id}");
+                        return Err(ScorchError::new(
+                            ErrorKind::Redefinition,
+                            format!("redefinition of variable {id}"),
+                        )
+                        .with_subject(id));
                     }
                     None => {
                         ctx.insert_variable(&id, Rc::new(RefCell::new(var)));
@@ -676,270 +1157,575 @@ impl Visitor<Value> for Interpreter {
                 }
             }
         } else {
-            panic!("Expected Declaration node");
+            return Err(ScorchError::new(ErrorKind::Internal, "Expected Declaration node"));
         }
-        Value::None()
+        Ok(Value::None())
     }
-    fn visit_assignment(&mut self, node: &Node) -> Value {
+    fn assignment(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
         match node {
             Node::AssignStmnt { id, expression } => {
-                
-                let id = id.accept(self);
-                
+
+                let id = id.accept(self)?;
+
                 let Value::Reference(id_val) = id else {
-                    panic!("Expected Reference {:?}", id);
+                    return Err(ScorchError::new(
+                        ErrorKind::TypeMismatch,
+                        format!("Expected Reference {:?}", id),
+                    ));
                 };
-                
-                let result = self.eval_deref(expression);
-                
+
+                let result = self.eval_deref(expression)?;
+
                 let mut id_val = id_val.borrow_mut();
-                
+
                 if !id_val.mutable {
-					panic!("cannot assign to const : {:?}", id_val);
+					return Err(ScorchError::new(
+                        ErrorKind::Immutable,
+                        format!("cannot assign to const : {:?}", id_val),
+                    ));
 				}
-                
-                id_val.set_value(&result);
-                
-                if !TypeChecker::validate(&id_val) {
-                    panic!("Invalid type {}", id_val.m_type.borrow().name);
+
+                // checked before assigning, a failed assignment leaves the variable as it was.
+                if !id_val.m_type.borrow().validate(&result) {
+                    return Err(ScorchError::new(
+                        ErrorKind::TypeMismatch,
+                        format!("Invalid type {}, recieved value : {:?}", id_val.m_type.borrow().name, result),
+                    ));
                 }
-                
-                return Value::None();
+
+                id_val.set_value(&result);
+
+                return Ok(Value::None());
             }
             _ => {
-                dbg!(node);
-                panic!("Expected Assignment node");
+                Err(ScorchError::new(ErrorKind::Internal, "Expected Assignment node"))
             }
         }
     }
-    
-    fn visit_identifier(&mut self, node: &Node) -> Value {
+    fn identifier(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
         let ctx = self.context.borrow_mut();
-        
+
         let Node::Identifier(id) = node else {
-            dbg!(node);
-            panic!("Expected Identifier");
+            return Err(ScorchError::new(ErrorKind::Internal, "Expected Identifier"));
         };
-        
+
         let var = ctx.find_variable(id);
-        
+
         let Some(var) = var else {
-            dbg!(node);
-            panic!("Variable {id} not found");
+            return Err(ScorchError::new(
+                ErrorKind::UndefinedVariable,
+                format!("Variable {id} not found"),
+            )
+            .with_subject(id));
         };
-        
-        Value::Reference(Rc::clone(&var))
+
+        Ok(Value::Reference(Rc::clone(&var)))
     }
-   
-    fn visit_bool(&mut self, node: &Node) -> Value {
-        if let Node::Bool(value) = node {
-            return Value::Bool(*value);
+    fn not_op(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
+        if let Node::NotOp(operand) = node {
+            match operand.accept(self)? {
+                Value::Bool(value) => Ok(Value::Bool(!value)),
+                other => Err(ScorchError::new(
+                    ErrorKind::TypeMismatch,
+                    format!("Expected boolean operand for unary not (!) operation, got {:?}", other),
+                )),
+            }
         } else {
-            panic!("Expected Bool node");
+            Err(ScorchError::new(ErrorKind::Internal, "Expected NotOp node"))
         }
     }
-    fn visit_number(&mut self, node: &Node) -> Value {
-        if let Node::Double(value) = node {
-            Value::Double(*value)
-        } else if let Node::Int(value) = node {
-            Value::Int(*value)
+    fn neg_op(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
+        if let Node::NegOp(operand) = node {
+            match operand.accept(self)? {
+                Value::Double(value) => Ok(Value::Double(-value)),
+                Value::Int(value) => match value.checked_neg() {
+                    Some(negated) => Ok(Value::Int(negated)),
+                    None => Err(overflow(format!("-({})", value))),
+                },
+                other => Err(ScorchError::new(
+                    ErrorKind::TypeMismatch,
+                    format!("Expected numeric operand for unary negation (-) operation, got {:?}", other),
+                )),
+            }
         } else {
-            dbg!(node);
-            panic!("Expected Number");
+            Err(ScorchError::new(ErrorKind::Internal, "Expected NegOp node"))
         }
     }
-    fn visit_string(&mut self, node: &Node) -> Value {
-        if let Node::String(value) = node {
-            return Value::String(value.clone());
-        } else {
-            panic!("Expected String node");
-        }
-    }
-    fn visit_eof(&mut self, _node: &Node) -> Value {
-        Value::None() // do nothing.
-    }
-    
-    // unary operations
-    fn visit_not_op(&mut self, node: &Node) -> Value {
-        if let Node::NotOp(operand) = node {
-            match operand.accept(self) {
-                Value::Bool(value) => Value::Bool(!value),
-                _ => panic!("Expected boolean operand for unary not (!) operation"),
-            }
-        } else {
-            panic!("Expected NotOp node");
-        }
-    }
-    fn visit_neg_op(&mut self, node: &Node) -> Value {
-        if let Node::NegOp(operand) = node {
-            match operand.accept(self) {
-                Value::Double(value) => Value::Double(-value),
-                Value::Int(value) => Value::Int(-value),
-                _ => panic!("Expected numeric operand for unary negation (-) operation"),
-            }
-        } else {
-            panic!("Expected NegOp node");
-        }
-    }
-    
-    // binary operations & expressions
-    fn visit_relational_expression(&mut self, node: &Node) -> Value {
+    fn relational_expression(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
         if let Node::RelationalExpression { lhs, op, rhs } = node {
-            
-            let lhs_value = self.eval_deref(lhs);
-            let rhs_value = self.eval_deref(rhs);
-            
+
+            let lhs_value = self.eval_deref(lhs)?;
+            let rhs_value = self.eval_deref(rhs)?;
+
+            let invalid_operator = || -> ScorchResult {
+                Err(ScorchError::new(
+                    ErrorKind::InvalidOperator,
+                    format!("invalid operator {:?} in relational expression", op),
+                ))
+            };
+
             match (lhs_value, rhs_value) {
                 (Value::Bool(lhs_bool), Value::Bool(rhs_bool)) => match op {
-                    TokenKind::Equals => return Value::Bool(lhs_bool == rhs_bool),
-                    TokenKind::NotEquals => return Value::Bool(lhs_bool != rhs_bool),
-                    _ => {
-                        dbg!(node);
-                        panic!("invalid operator");
-                    }
+                    TokenKind::Equals => return Ok(Value::Bool(lhs_bool == rhs_bool)),
+                    TokenKind::NotEquals => return Ok(Value::Bool(lhs_bool != rhs_bool)),
+                    _ => invalid_operator(),
                 },
                 (Value::Int(lhs_int), Value::Int(lhs_table)) => match op {
-                    TokenKind::LeftAngle => return Value::Bool(lhs_int < lhs_table),
-                    TokenKind::LessThanEquals => return Value::Bool(lhs_int <= lhs_table),
-                    TokenKind::RightAngle => return Value::Bool(lhs_int > lhs_table),
-                    TokenKind::GreaterThanEquals => return Value::Bool(lhs_int >= lhs_table),
-                    TokenKind::Equals => return Value::Bool(lhs_int == lhs_table),
-                    TokenKind::NotEquals => return Value::Bool(lhs_int != lhs_table),
-                    _ => {
-                        dbg!(node);
-                        panic!("invalid operator");
-                    }
+                    TokenKind::LeftAngle => return Ok(Value::Bool(lhs_int < lhs_table)),
+                    TokenKind::LessThanEquals => return Ok(Value::Bool(lhs_int <= lhs_table)),
+                    TokenKind::RightAngle => return Ok(Value::Bool(lhs_int > lhs_table)),
+                    TokenKind::GreaterThanEquals => return Ok(Value::Bool(lhs_int >= lhs_table)),
+                    TokenKind::Equals => return Ok(Value::Bool(lhs_int == lhs_table)),
+                    TokenKind::NotEquals => return Ok(Value::Bool(lhs_int != lhs_table)),
+                    _ => invalid_operator(),
                 },
                 (Value::Int(lhs_float), Value::Double(rhs_float)) => match op {
-                    TokenKind::LeftAngle => return Value::Bool((lhs_float as f64) < rhs_float),
+                    TokenKind::LeftAngle => return Ok(Value::Bool((lhs_float as f64) < rhs_float)),
                     TokenKind::LessThanEquals => {
-                        return Value::Bool((lhs_float as f64) <= rhs_float)
+                        return Ok(Value::Bool((lhs_float as f64) <= rhs_float))
                     }
-                    TokenKind::RightAngle => return Value::Bool((lhs_float as f64) > rhs_float),
+                    TokenKind::RightAngle => return Ok(Value::Bool((lhs_float as f64) > rhs_float)),
                     TokenKind::GreaterThanEquals => {
-                        return Value::Bool((lhs_float as f64) >= rhs_float)
-                    }
-                    TokenKind::Equals => return Value::Bool((lhs_float as f64) == rhs_float),
-                    TokenKind::NotEquals => return Value::Bool((lhs_float as f64) != rhs_float),
-                    _ => {
-                        dbg!(node);
-                        panic!("invalid operator");
+                        return Ok(Value::Bool((lhs_float as f64) >= rhs_float))
                     }
+                    TokenKind::Equals => return Ok(Value::Bool((lhs_float as f64) == rhs_float)),
+                    TokenKind::NotEquals => return Ok(Value::Bool((lhs_float as f64) != rhs_float)),
+                    _ => invalid_operator(),
                 },
                 (Value::Double(lhs_float), Value::Int(rhs_float)) => match op {
-                    TokenKind::LeftAngle => return Value::Bool(lhs_float < (rhs_float as f64)),
+                    TokenKind::LeftAngle => return Ok(Value::Bool(lhs_float < (rhs_float as f64))),
                     TokenKind::LessThanEquals => {
-                        return Value::Bool(lhs_float <= (rhs_float as f64))
+                        return Ok(Value::Bool(lhs_float <= (rhs_float as f64)))
                     }
-                    TokenKind::RightAngle => return Value::Bool(lhs_float > (rhs_float as f64)),
+                    TokenKind::RightAngle => return Ok(Value::Bool(lhs_float > (rhs_float as f64))),
                     TokenKind::GreaterThanEquals => {
-                        return Value::Bool(lhs_float >= (rhs_float as f64))
-                    }
-                    TokenKind::Equals => return Value::Bool(lhs_float == (rhs_float as f64)),
-                    TokenKind::NotEquals => return Value::Bool(lhs_float != (rhs_float as f64)),
-                    _ => {
-                        dbg!(node);
-                        panic!("invalid operator");
+                        return Ok(Value::Bool(lhs_float >= (rhs_float as f64)))
                     }
+                    TokenKind::Equals => return Ok(Value::Bool(lhs_float == (rhs_float as f64))),
+                    TokenKind::NotEquals => return Ok(Value::Bool(lhs_float != (rhs_float as f64))),
+                    _ => invalid_operator(),
                 },
                 (Value::Double(lhs_float), Value::Double(rhs_float)) => match op {
-                    TokenKind::LeftAngle => return Value::Bool(lhs_float < rhs_float),
-                    TokenKind::LessThanEquals => return Value::Bool(lhs_float <= rhs_float),
-                    TokenKind::RightAngle => return Value::Bool(lhs_float > rhs_float),
-                    TokenKind::GreaterThanEquals => return Value::Bool(lhs_float >= rhs_float),
-                    TokenKind::Equals => return Value::Bool(lhs_float == rhs_float),
-                    TokenKind::NotEquals => return Value::Bool(lhs_float != rhs_float),
-                    _ => {
-                        dbg!(node);
-                        panic!("invalid operator");
-                    }
+                    TokenKind::LeftAngle => return Ok(Value::Bool(lhs_float < rhs_float)),
+                    TokenKind::LessThanEquals => return Ok(Value::Bool(lhs_float <= rhs_float)),
+                    TokenKind::RightAngle => return Ok(Value::Bool(lhs_float > rhs_float)),
+                    TokenKind::GreaterThanEquals => return Ok(Value::Bool(lhs_float >= rhs_float)),
+                    TokenKind::Equals => return Ok(Value::Bool(lhs_float == rhs_float)),
+                    TokenKind::NotEquals => return Ok(Value::Bool(lhs_float != rhs_float)),
+                    _ => invalid_operator(),
                 },
                 (Value::String(lhs_string), Value::String(rhs_string)) => match op {
-                    TokenKind::Equals => return Value::Bool(lhs_string == rhs_string),
-                    TokenKind::NotEquals => return Value::Bool(lhs_string != rhs_string),
-                    _ => {
-                        dbg!(node);
-                        panic!("invalid operator");
-                    }
+                    TokenKind::Equals => return Ok(Value::Bool(lhs_string == rhs_string)),
+                    TokenKind::NotEquals => return Ok(Value::Bool(lhs_string != rhs_string)),
+                    _ => invalid_operator(),
                 },
-                _ => {
-                    self.evaluate_expression(lhs, rhs, op)
-                }   
+                (lhs_value, rhs_value) => {
+                    // arrays & structs compare structurally, unless a struct overloads the operator.
+                    let structural = matches!(
+                        (&lhs_value, &rhs_value),
+                        (Value::Array(..), Value::Array(..)) | (Value::StructInstance { .. }, Value::StructInstance { .. })
+                    );
+                    let overloaded = self.has_operator(&lhs_value, &rhs_value, op);
+                    let has_eq = self.has_operator(&lhs_value, &rhs_value, &TokenKind::Equals);
+                    match op {
+                        _ if overloaded => self.apply_operator(&lhs_value, &rhs_value, op),
+                        // without a ne overload, != negates eq.
+                        TokenKind::NotEquals if has_eq => {
+                            match self.apply_operator(&lhs_value, &rhs_value, &TokenKind::Equals)? {
+                                Value::Bool(equal) => Ok(Value::Bool(!equal)),
+                                other => Err(ScorchError::new(
+                                    ErrorKind::TypeMismatch,
+                                    format!("eq should return bool, but returned {}", other),
+                                )),
+                            }
+                        }
+                        TokenKind::Equals | TokenKind::NotEquals if structural => {
+                            let equal = lhs_value.deep_eq(&rhs_value);
+                            Ok(Value::Bool(equal == (*op == TokenKind::Equals)))
+                        }
+                        _ => self.apply_operator(&lhs_value, &rhs_value, op),
+                    }
+                }
             }
         } else {
-            panic!("Expected RelativeExpression node");
+            Err(ScorchError::new(ErrorKind::Internal, "Expected RelationalExpression node"))
         }
     }
-        
-    fn visit_logical_expression(&mut self, node: &Node) -> Value {
+    fn logical_expression(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
         if let Node::LogicalExpression { lhs, op, rhs } = node {
-            let lhs_value = self.eval_deref(lhs);
-            let rhs_value = self.eval_deref(rhs);
-            match (lhs_value, rhs_value) {
-                (Value::Bool(lhs_bool), Value::Bool(rhs_bool)) => match op {
-                    TokenKind::LogicalAnd => return Value::Bool(lhs_bool && rhs_bool),
-                    TokenKind::LogicalOr => return Value::Bool(lhs_bool || rhs_bool),
-                    _ => {
-                        dbg!(node);
-                        panic!("invalid operator for logical / boolean expression");
-                    }
-                },
+            let lhs_value = self.eval_deref(lhs)?;
+
+            let Value::Bool(lhs_bool) = lhs_value else {
+                // only an operator overload can combine these, and it needs both sides.
+                let rhs_value = self.eval_deref(rhs)?;
+                return self.apply_operator(&lhs_value, &rhs_value, op);
+            };
+
+            // the rhs is only evaluated when it decides the result.
+            match op {
+                TokenKind::LogicalAnd if !lhs_bool => return Ok(Value::Bool(false)),
+                TokenKind::LogicalOr if lhs_bool => return Ok(Value::Bool(true)),
+                TokenKind::LogicalAnd | TokenKind::LogicalOr => {}
                 _ => {
-                    self.evaluate_expression(lhs, rhs, op)
+                    return Err(ScorchError::new(
+                        ErrorKind::InvalidOperator,
+                        format!("invalid operator {:?} for logical / boolean expression", op),
+                    ))
                 }
             }
+
+            match self.eval_deref(rhs)? {
+                Value::Bool(rhs_bool) => Ok(Value::Bool(rhs_bool)),
+                rhs_value => self.apply_operator(&Value::Bool(lhs_bool), &rhs_value, op),
+            }
         } else {
-            panic!("Expected LogicalExpression node");
+            Err(ScorchError::new(ErrorKind::Internal, "Expected LogicalExpression node"))
         }
     }
-    fn visit_expression(&mut self, node: &Node) -> Value {
-        if let Node::Expression(root) = node {
-            return root.accept(self);
+    fn binary_op(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
+        let Node::BinaryOperation { lhs, op, rhs } = node else {
+            return Err(ScorchError::new(ErrorKind::Internal, "Expected binary operation node"));
+        };
+        if let TokenKind::Dot = op {
+            return self.dot_op(lhs, rhs);
+        }
+
+        let e_lhs = self.eval_deref(lhs)?;
+        let e_rhs = self.eval_deref(rhs)?;
+        match (e_lhs, e_rhs) {
+            (Value::Int(lhs_int), Value::Int(rhs_int)) => self.bin_op_int(node, &lhs_int, &rhs_int),
+            (Value::Double(lhs_float), Value::Double(rhs_float)) => self.bin_op_float(node, &lhs_float, &rhs_float),
+            // ints are promoted to doubles, like in relational expressions.
+            (Value::Int(lhs_int), Value::Double(rhs_float)) => self.bin_op_float(node, &(lhs_int as f64), &rhs_float),
+            (Value::Double(lhs_float), Value::Int(rhs_int)) => self.bin_op_float(node, &lhs_float, &(rhs_int as f64)),
+            (Value::String(lhs_string), Value::String(rhs_string)) => self.bin_op_string(node, &lhs_string, &rhs_string),
+            (e_lhs, e_rhs) => self.apply_operator(&e_lhs, &e_rhs, op),
+        }
+    }
+    fn function_call(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
+        let (id, arguments) = match node {
+            Node::FunctionCall { id, arguments } => (id, arguments),
+            _ => return Ok(Value::None()),
+        };
+        self.try_find_and_execute_fn(arguments, id)
+    }
+    fn array_access(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
+        let (id, index, expression, assignment) = match node {
+            Node::ArrayAccessExpr {
+                id,
+                index_expr: index,
+                expression,
+                assignment,
+            } => (id, index, expression, assignment),
+            _ => return Err(ScorchError::new(ErrorKind::Internal, "Expected ArrayAccessExpr node")),
+        };
+
+		let val = self.eval_deref(index)?;
+        let index_value = match val {
+            Value::Double(index_value) => index_value as usize,
+            Value::Int(index_value) => index_value as usize,
+            _ => {
+                return Err(ScorchError::new(
+                    ErrorKind::TypeMismatch,
+                    format!("Expected numerical index value, got {:?}", val),
+                )
+                .with_subject(id))
+            }
+        };
+
+        if *assignment {
+            if let Some(expr) = expression {
+                let result = expr.accept(self)?;
+                self.assign_to_array(id, index_value, result)?;
+            } else {
+                return Err(ScorchError::new(
+                    ErrorKind::Internal,
+                    "Expected expression in array assignment",
+                ));
+            }
+            Ok(Value::None())
         } else {
-            panic!("Expected Expression node");
+            self.access_array(id, index_value)
         }
     }
-    fn visit_binary_op(&mut self, node: &Node) -> Value {
-        let Node::BinaryOperation { lhs, op, rhs } = node else {
-            dbg!(node);
-            panic!("Expected binary operation node");
+    fn struct_init(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
+        let Node::StructInit { id, arguments } = node else {
+            return Err(ScorchError::new(ErrorKind::Internal, "Expected StructInit node"));
         };
-        match op {
-            TokenKind::Dot => self.dot_op(lhs, rhs),
-            TokenKind::Add | TokenKind::Divide | TokenKind::Multiply | TokenKind::Subtract => {
-                let e_lhs = self.eval_deref(lhs);
-                let e_rhs = self.eval_deref(rhs);
-                match (e_lhs, e_rhs) {
-                    (Value::Int(lhs_float), Value::Int(rhs_float)) => self.bin_op_int(node, &lhs_float, &rhs_float),
-                    (Value::Double(lhs_float), Value::Double(rhs_float)) => self.bin_op_float(node, &lhs_float, &rhs_float),
-                    (Value::String(lhs_string), Value::String(rhs_string)) => self.bin_op_string(node, &lhs_string, &rhs_string),
-                    _ => self.evaluate_expression(lhs, rhs, op)
+
+        let typedef = if let Some(type_) = self.type_checker.types.get_mut(id) {
+            type_
+        } else {
+            return Err(ScorchError::new(
+                ErrorKind::UndefinedType,
+                format!("Struct {} not found", id),
+            )
+            .with_subject(id));
+        };
+
+        let (mut struct_context, field_decls, scope) = {
+            let typedef = typedef.borrow();
+            (typedef.context.clone(), typedef.field_decls.clone(), typedef.scope.clone())
+        };
+
+        // the type's context holds the shared associated functions, every instance gets its own fields.
+        let mut fields = Vec::new();
+        if let (Some(field_decls), Some(scope)) = (field_decls, scope) {
+            let Some(scope) = scope.scope() else {
+                return Err(ScorchError::new(
+                    ErrorKind::Internal,
+                    format!("the scope {} was declared in no longer exists", id),
+                )
+                .with_subject(id));
+            };
+            let instance_fields = self.instance_fields(&field_decls, scope)?;
+            fields = instance_fields.keys().cloned().collect();
+            struct_context.variables.extend(instance_fields);
+        }
+
+        let fields = Rc::new(fields);
+        let instance = Value::StructInstance {
+            typename: id.clone(),
+            context: struct_context,
+            fields: Rc::clone(&fields),
+        };
+        self.construct(id, &instance, &fields, arguments)?;
+        Ok(instance)
+    }
+}
+impl Visitor<ScorchResult> for Interpreter {
+    // top level nodes
+    fn visit_program(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
+        let statements = match node {
+            Node::Program(statements) => statements,
+            _ => return Err(ScorchError::new(ErrorKind::Internal, "expected program node")),
+        };
+
+        // the program evaluates to a top level return, or else the last statement that produced a value.
+        let mut last = Value::None();
+
+        for stmnt in statements {
+            let result = stmnt.accept(self);
+            let result = self.at(stmnt, result)?;
+            match result {
+                Value::Return(Some(return_value)) => return Ok(return_value.dereference()),
+                Value::Return(None) => return Ok(Value::None()),
+                Value::None() => continue,
+                _ => last = result.dereference(),
+            }
+        }
+
+        Ok(last)
+    }
+    fn visit_block(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
+        let statements = match node {
+            Node::Block(statements) => statements,
+            _ => return Err(ScorchError::new(ErrorKind::Internal, "Expected Block node")),
+        };
+
+        for statement in statements {
+            let value = statement.accept(self);
+            let value = self.at(statement, value)?;
+            match value {
+                Value::Return(..) => return Ok(value),
+                _ => continue,
+            }
+        }
+
+        Ok(Value::None())
+    }
+
+    // statements
+    fn visit_if_stmnt(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
+        let (condition, true_block, else_block) = match node {
+            Node::IfStmnt {
+                condition,
+                block: true_block,
+                else_stmnt: else_block,
+            } => (condition, true_block, else_block),
+            _ => return Err(ScorchError::new(ErrorKind::Internal, "Expected IfStmnt node")),
+        };
+
+        let condition_result = match condition.accept(self)? {
+            Value::Bool(condition_result) => condition_result,
+            other => {
+                return Err(ScorchError::new(
+                    ErrorKind::TypeMismatch,
+                    format!("Expected boolean condition, got {:?}", other),
+                ))
+            }
+        };
+
+        if condition_result {
+            self.push_ctx();
+            let returned = true_block.accept(self);
+            self.pop_ctx()?;
+            let returned = returned?;
+            match returned {
+                Value::Return(_) => return Ok(returned),
+                _ => {}
+            }
+        } else if let Some(else_stmnt) = else_block {
+            let returned = else_stmnt.accept(self)?;
+            match returned {
+                Value::Return(_) => return Ok(returned),
+                _ => {}
+            }
+        }
+
+        Ok(Value::None())
+    }
+    fn visit_else_stmnt(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
+        let (condition, true_block, else_stmnt) = match node {
+            Node::ElseStmnt {
+                condition,
+                block: true_block,
+                else_stmnt,
+            } => (condition, true_block, else_stmnt),
+            _ => return Err(ScorchError::new(ErrorKind::Internal, "Expected ElseStmnt node")),
+        };
+
+        let condition_result = match condition.as_ref() {
+            Some(expression) => match expression.accept(self)? {
+                Value::Bool(val) => val,
+                other => {
+                    return Err(ScorchError::new(
+                        ErrorKind::TypeMismatch,
+                        format!("Expected boolean condition, got {:?}", other),
+                    ))
                 }
+            },
+            None => true,
+        };
+
+        if condition_result {
+            self.push_ctx();
+            let returned = true_block.accept(self);
+            self.pop_ctx()?;
+            let returned = returned?;
+
+            match returned {
+                Value::Return(_) => return Ok(returned),
+                _ => {}
             }
-            _ => self.evaluate_expression(lhs, rhs, op),
+
+        } else if let Some(else_statement) = else_stmnt {
+            else_statement.accept(self)?;
         }
+
+        Ok(Value::None())
     }
-    fn visit_term(&mut self, _node: &Node) -> Value {
-        return Value::None();
+    fn visit_declaration(&mut self, node: &Node) -> ScorchResult {
+        let result = self.declaration(node);
+        self.at(node, result)
     }
-    fn visit_factor(&mut self, node: &Node) -> Value {
+    fn visit_assignment(&mut self, node: &Node) -> ScorchResult {
+        let result = self.assignment(node);
+        self.at(node, result)
+    }
+
+    fn visit_identifier(&mut self, node: &Node) -> ScorchResult {
+        let result = self.identifier(node);
+        self.at(node, result)
+    }
+
+    fn visit_bool(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
+        if let Node::Bool(value) = node {
+            return Ok(Value::Bool(*value));
+        } else {
+            Err(ScorchError::new(ErrorKind::Internal, "Expected Bool node"))
+        }
+    }
+    fn visit_number(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
+        if let Node::Double(value) = node {
+            Ok(Value::Double(*value))
+        } else if let Node::Int(value) = node {
+            Ok(Value::Int(i64::from(*value)))
+        } else {
+            Err(ScorchError::new(ErrorKind::Internal, "Expected Number"))
+        }
+    }
+    fn visit_string(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
+        if let Node::String(value) = node {
+            return Ok(Value::String(value.clone()));
+        } else {
+            Err(ScorchError::new(ErrorKind::Internal, "Expected String node"))
+        }
+    }
+    fn visit_eof(&mut self, _node: &Node) -> ScorchResult {
+        self.step()?;
+        Ok(Value::None()) // do nothing.
+    }
+
+    // unary operations
+    fn visit_not_op(&mut self, node: &Node) -> ScorchResult {
+        let result = self.not_op(node);
+        self.at(node, result)
+    }
+    fn visit_neg_op(&mut self, node: &Node) -> ScorchResult {
+        let result = self.neg_op(node);
+        self.at(node, result)
+    }
+
+    // binary operations & expressions
+    fn visit_relational_expression(&mut self, node: &Node) -> ScorchResult {
+        let result = self.relational_expression(node);
+        self.at(node, result)
+    }
+
+    fn visit_logical_expression(&mut self, node: &Node) -> ScorchResult {
+        let result = self.logical_expression(node);
+        self.at(node, result)
+    }
+    fn visit_expression(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
+        if let Node::Expression(root) = node {
+            return root.accept(self);
+        } else {
+            Err(ScorchError::new(ErrorKind::Internal, "Expected Expression node"))
+        }
+    }
+    fn visit_binary_op(&mut self, node: &Node) -> ScorchResult {
+        let result = self.binary_op(node);
+        self.at(node, result)
+    }
+    fn visit_term(&mut self, _node: &Node) -> ScorchResult {
+        self.step()?;
+        return Ok(Value::None());
+    }
+    fn visit_factor(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
         match node {
             Node::Expression(root) => root.accept(self),
             _ => {
-                dbg!(node);
-                panic!("Expected Number or Identifier node");
+                Err(ScorchError::new(ErrorKind::Internal, "Expected Number or Identifier node"))
             }
         }
     }
-  
-    fn visit_function_call(&mut self, node: &Node) -> Value {
-        let (id, arguments) = match node {
-            Node::FunctionCall { id, arguments } => (id, arguments),
-            _ => return Value::None(),
-        };
-        self.try_find_and_execute_fn(arguments, id)
+
+    fn visit_function_call(&mut self, node: &Node) -> ScorchResult {
+        let result = self.function_call(node);
+        self.at(node, result)
     }
-    fn visit_function_decl(&mut self, node: &Node) -> Value {
+    fn visit_function_decl(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
         if let Node::FnDeclStmnt {
             id,
             params,
@@ -948,20 +1734,33 @@ impl Visitor<Value> for Interpreter {
             mutable,
         } = node
         {
-            let body_cloned = body.clone();
+            let body_cloned = Rc::new(body.as_ref().clone());
+            let mut spans = Spans::new();
+            spans.copy_from(self.current_spans(), body, &body_cloned);
             let Some(r_type) = self.type_checker.get(return_type) else {
-                panic!("FnDecl: {} not a valid return type", return_type);
+                return Err(ScorchError::new(
+                    ErrorKind::UndefinedType,
+                    format!("FnDecl: {} not a valid return type", return_type),
+                )
+                .with_subject(return_type));
             };
+            // a function without a name is an expression, its value is the function itself.
+            let anonymous = id.is_empty();
             let func = Function {
-                name: id.to_string(),
-                params: self.get_params_list(params),
+                name: if anonymous { String::from("<anonymous>") } else { id.to_string() },
+                params: self.get_params_list(params)?,
                 body: body_cloned,
+                spans: Rc::new(spans),
                 return_type: r_type,
                 mutable: *mutable,
+                closure: self.capture(),
             };
+            if anonymous {
+                return Ok(Value::Function(Rc::new(func)));
+            }
             // Todo: we might want to have a better way to do this than just getting it by string
             let Some(m_type) = self.type_checker.get(FN_TNAME) else {
-                panic!("Fn isn't a type");
+                return Err(ScorchError::new(ErrorKind::Internal, "Fn isn't a type"));
             };
             let function = Instance {
                 mutable: *mutable,
@@ -972,20 +1771,20 @@ impl Visitor<Value> for Interpreter {
                 .borrow_mut()
                 .insert_variable(&id, Rc::new(RefCell::new(function)));
         } else {
-            panic!("Expected FunctionDecl node");
+            return Err(ScorchError::new(ErrorKind::Internal, "Expected FunctionDecl node"));
         };
-        Value::None()
+        Ok(Value::None())
     }
-    
-    fn visit_repeat_stmnt(&mut self, node: &Node) -> Value {
+
+    fn visit_repeat_stmnt(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
         let Node::RepeatStmnt {
             iterator_id,
             condition,
             block,
         } = node
         else {
-            dbg!(node);
-            panic!("Expected RepeatStmnt node");
+            return Err(ScorchError::new(ErrorKind::Internal, "Expected RepeatStmnt node"));
         };
 
         match iterator_id {
@@ -996,18 +1795,21 @@ impl Visitor<Value> for Interpreter {
             None => self.visit_conditionless_repeat_stmnt(block),
         }
     }
-    fn visit_break_stmnt(&mut self, node: &Node) -> Value {
+    fn visit_break_stmnt(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
         if let Node::ReturnStmnt(opt_val) = node {
             let Some(value_node) = opt_val else {
-                return Value::Return(None);
+                return Ok(Value::Return(None));
             };
-            let value = value_node.accept(self);
-            return Value::Return(Some(Box::new(value.clone())));
+            // copied out of its variable, the scope it's in is released on the way out.
+            let value = self.eval_deref(value_node)?;
+            return Ok(Value::Return(Some(Box::new(value))));
         } else {
-            panic!("Expected ReturnStmnt node");
+            Err(ScorchError::new(ErrorKind::Internal, "Expected ReturnStmnt node"))
         }
     }
-    fn visit_array(&mut self, node: &Node) -> Value {
+    fn visit_array(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
         if let Node::Array {
             typename: _,
             init_capacity,
@@ -1018,149 +1820,128 @@ impl Visitor<Value> for Interpreter {
         {
             let len = *init_capacity;
             if len < elements.len() {
-                panic!("Array length is less than the number of elements");
+                return Err(ScorchError::new(
+                    ErrorKind::IndexOutOfBounds,
+                    "Array length is less than the number of elements",
+                ));
             }
             let mut values = Vec::with_capacity(len);
             for value in elements {
-                let val = value.accept(self);
+                // elements are copied out of variables, an array holds values not references.
+                let val = self.eval_deref(value)?;
                 // TODO curently not checking if type is valid
                 let Some(m_type) = self.type_checker.from_value(&val) else {
-                    dbg!(&node);
-                    panic!("{:?} doesn't match to a valid type", val);
+                    return Err(ScorchError::new(
+                        ErrorKind::UndefinedType,
+                        format!("{:?} doesn't match to a valid type", val),
+                    ));
                 };
                 let var = Instance::new(*elements_mutable, val, m_type);
                 values.push(Rc::new(RefCell::new(var)));
             }
-            
-            return Value::Array(*mutability, Rc::new(RefCell::new(values)));
-        } else {
-            panic!("Expected List node");
-        }
-    }
-    fn visit_array_access(&mut self, node: &Node) -> Value {
-        let (id, index, expression, assignment) = match node {
-            Node::ArrayAccessExpr {
-                id,
-                index_expr: index,
-                expression,
-                assignment,
-            } => (id, index, expression, assignment),
-            _ => panic!("Expected ArrayAccessExpr node"),
-        };
-        
-		let val = self.eval_deref(index);
-        let index_value = match val {
-            Value::Double(index_value) => index_value as usize,
-            Value::Int(index_value) => index_value as usize,
-            _ => panic!("Expected numerical index value, got {:?}", val),
-        };
 
-        if *assignment {
-            if let Some(expr) = expression {
-                let result = expr.accept(self);
-                self.assign_to_array(id, index_value, result);
-            } else {
-                panic!("Expected expression in array assignment");
-            }
-            Value::None()
+            return Ok(Value::Array(*mutability, Rc::new(RefCell::new(values))));
         } else {
-            self.access_array(id, index_value)
+            Err(ScorchError::new(ErrorKind::Internal, "Expected List node"))
         }
     }
-   
-    fn visit_struct_def(&mut self, node: &Node) -> Value {
+    fn visit_array_access(&mut self, node: &Node) -> ScorchResult {
+        let result = self.array_access(node);
+        self.at(node, result)
+    }
+
+    fn visit_struct_def(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
         if let Node::StructDecl { id, block } = node {
             let Node::Block(_statements) = block.as_ref() else {
-                panic!("Expected block")
+                return Err(ScorchError::new(ErrorKind::Internal, "Expected block"));
             };
-            
-            self.push_ctx();
-            
-            let mut fields = Vec::<(String, Rc<RefCell<Type>>)>::new();
-            
-            for statement in _statements {
-                let Node::DeclStmt {
-                    target_type, id, ..
-                } = statement.as_ref()
-                else {
-                    panic!("Expected declaration, got {:#?}", statement);
-                };
-                
-                let Some(t) = self.type_checker.get(&target_type) else {
-                    panic!("{} not a valid type", target_type);
-                };
-                
-                fields.push((id.clone(), t));
-                statement.accept(self);
+
+            // the defaults aren't evaluated here, each instance evaluates its own.
+            self.struct_fields(_statements)?;
+            // holds the associated functions, the fields live on the instances. it has no parent,
+            // instances are stored in scopes and would keep them alive.
+            let context = Box::new(Context {
+                parent: None,
+                variables: IndexMap::new(),
+            });
+
+            let statements = _statements.clone();
+            let mut spans = Spans::new();
+            for (statement, decl) in _statements.iter().zip(&statements) {
+                spans.copy_from(self.current_spans(), statement, decl);
             }
-            
+            let field_decls = FieldDecls {
+                statements,
+                spans: Rc::new(spans),
+            };
+
+            let name = id.clone();
             let _new_type = Type {
                 name: id.to_string(),
-                validator: Box::new(|_value| true),
+                // struct types are nominal, only instances of this struct are accepted.
+                validator: Box::new(move |value| matches!(value, Value::StructInstance { typename, .. } if *typename == name)),
                 attribute: Attr::Struct,
-                // todo : make sure this clones fully and doesn't just copy the reference.
-                context: Box::new(self.context.borrow().to_owned()),
+                context,
                 operators: Vec::new(),
+                field_decls: Some(Rc::new(field_decls)),
+                scope: Some(self.capture()),
             };
-            
+
             self.type_checker.types.insert(id.to_string(), Rc::new(RefCell::new(_new_type)));
-            
-            self.pop_ctx();
         }
-        Value::None()
+        Ok(Value::None())
     }
-    fn visit_struct_init(&mut self, node: &Node) -> Value {
-        let Node::StructInit { id, .. } = node else {
-            panic!("Expected StructInit node");
-        };
-        
-        let typedef = if let Some(type_) = self.type_checker.types.get_mut(id) {
-            type_
-        } else {
-            panic!("Struct {} not found", id);
-        };
-        
-        let struct_context = typedef.borrow().context.clone();
-        
-        Value::StructInstance {
-            typename: id.clone(),
-            context: struct_context,
-        }
+    fn visit_struct_init(&mut self, node: &Node) -> ScorchResult {
+        let result = self.struct_init(node);
+        self.at(node, result)
     }
-    fn visit_type_assoc_block(&mut self, node: &Node) -> Value {
+    fn visit_type_assoc_block(&mut self, node: &Node) -> ScorchResult {
+        self.step()?;
         let Node::TypeAssocBlock { typename, block } = node else {
-            panic!("Expected TypeAssocBlock node");
+            return Err(ScorchError::new(ErrorKind::Internal, "Expected TypeAssocBlock node"));
         };
-        
+
         let typename = typename.clone();
         let type_ = if let Some(struct_) = self.type_checker.types.get_mut(&typename) {
             struct_
         } else {
-            panic!("Struct {} not found", typename);
+            return Err(ScorchError::new(
+                ErrorKind::UndefinedType,
+                format!("Struct {} not found", typename),
+            )
+            .with_subject(typename));
         };
-        
+
         // clone boxed context
         let mut type_context = type_.borrow_mut().context.clone();
-        
+
         type_context.parent = Some(Rc::clone(&self.context));
-        
+
         // make rc to ctx
         let rc = Rc::new(RefCell::new(*type_context));
-        
+
         self.context = Rc::clone(&rc);
-        
+
         let ctx = Some(Rc::clone(&rc));
-        
-        block.accept(self);
-        
+
+        let result = block.accept(self);
+
         if let Some(ctx) = ctx {
             if let Some(_struct) = self.type_checker.get(&typename) {
-                _struct.borrow_mut().context = Box::new(ctx.borrow().to_owned());
+                let mut type_context = ctx.borrow().to_owned();
+                type_context.parent = None;
+                _struct.borrow_mut().context = Box::new(type_context);
             }
         }
-        
-        self.pop_ctx();
-        
-        Value::None()
+
+        self.pop_ctx()?;
+        // the associated functions outlive the block, as long as the type does.
+        self.assoc_scopes.push(rc);
+
+        result?;
+
+        self.register_operators(&typename, block)?;
+        Ok(Value::None())
     }
 }
@@ -1,11 +1,18 @@
+pub mod analyzer;
 pub mod cli;
+pub mod config;
 pub mod context;
+pub mod error;
 pub mod interpreter;
+pub mod native;
+pub mod span;
 pub mod standard_functions;
+pub mod streams;
 pub mod typechecker;
 pub mod types;
 
 use ::std::collections::HashMap;
+use error::{ErrorKind, ScorchError};
 use indexmap::IndexMap;
 use interpreter::*;
 use scorch_parser::{lexer::{*, self}, parser};
@@ -14,7 +21,14 @@ use types::Value;
 #[cfg(test)]
 pub mod test;
 
-pub fn run<'a>(code : &'a String) -> Result<&'a Value, String> {
+pub fn run(code : &String) -> Result<Value, ScorchError> {
+    let mut interpreter = Interpreter::new();
+    run_with_interpreter(&mut interpreter, code)
+}
+// runs code against an existing interpreter, so hosts can register functions beforehand
+// and inspect its state afterwards.
+pub fn run_with_interpreter(interpreter : &mut Interpreter, code : &String) -> Result<Value, ScorchError> {
+    interpreter.reset_limits();
     
     let mut lexer = lexer::create_tokenizer();
     
@@ -22,28 +36,44 @@ pub fn run<'a>(code : &'a String) -> Result<&'a Value, String> {
     
     let tokens = &lexer.tokens;
     
-    let mut interpreter = Interpreter::new();
-    
-    let ast_root = parser::parse_program(&tokens);
-        
-        let Ok(ast_root) = ast_root else {
-            let Err(err) = ast_root else {
-                panic!("Failed to parse input:");
-            };
-            
-            dbg!(err);
-            panic!();
-        };
+    let ast_root = match parser::parse_program(&tokens) {
+        Ok(root) => root,
+        Err(err) => {
+            return Err(ScorchError::new(ErrorKind::Parse, format!("Failed to parse input : {:?}", err)));
+        }
+    };
         
-    ast_root.accept(&mut interpreter);
-    
-    Ok(&Value::None())   
+    interpreter.spans.record(code, None, tokens, &ast_root);
+    ast_root.accept(interpreter)
 }
-pub fn run_with_modules<'a>(code_array : IndexMap<String, Vec<String>>) -> Result<Value, String> {
+// statically checks code without running it, returning every problem found.
+pub fn check(code : &String) -> Vec<ScorchError> {
+    check_with_analyzer(&mut analyzer::Analyzer::new(), code)
+}
+// checks code against what an interpreter has defined, so host functions and globals are known.
+pub fn check_with_interpreter(interpreter : &Interpreter, code : &String) -> Vec<ScorchError> {
+    check_with_analyzer(&mut analyzer::Analyzer::for_interpreter(interpreter), code)
+}
+fn check_with_analyzer(analyzer : &mut analyzer::Analyzer, code : &String) -> Vec<ScorchError> {
+    let mut lexer = lexer::create_tokenizer();
+    lexer.tokenize(&code);
+    let tokens = &lexer.tokens;
+
+    let ast_root = match parser::parse_program(&tokens) {
+        Ok(root) => root,
+        Err(err) => {
+            return vec![ScorchError::new(ErrorKind::Parse, format!("Failed to parse input : {:?}", err))];
+        }
+    };
+
+    analyzer.spans.record(code, None, tokens, &ast_root);
+    analyzer.check(&ast_root)
+}
+pub fn run_with_modules<'a>(code_array : IndexMap<String, Vec<String>>) -> Result<Value, ScorchError> {
     let mut interpreter = Interpreter::new();
     let mut result : Option<Value> = None;    
     
-    for (_module_name, code_vec) in &code_array {
+    for (module_name, code_vec) in &code_array {
         for code in code_vec {
             let mut lexer = lexer::create_tokenizer();
             lexer.tokenize(&code);
@@ -52,17 +82,18 @@ pub fn run_with_modules<'a>(code_array : IndexMap<String, Vec<String>>) -> Resul
             let ast_root = match parser::parse_program(&tokens) {
                 Ok(root) => root,
                 Err(err) => {
-                    dbg!(err);
-                    panic!("Failed to parse input");
+                    return Err(ScorchError::new(ErrorKind::Parse, format!("Failed to parse input : {:?}", err))
+                        .with_subject(module_name));
                 }
             };
                 
-            result = Some(ast_root.accept(&mut interpreter));
+            interpreter.spans.record(code, Some(module_name.as_str()), tokens, &ast_root);
+            result = Some(ast_root.accept(&mut interpreter)?);
         }
     }
     
     match result {
         Some(value) => Ok(value),
-        None => Err("No result".to_string())
+        None => Err(ScorchError::new(ErrorKind::Internal, "No result"))
     }
-}
\ No newline at end of file
+}
@@ -1,11 +1,16 @@
+pub mod analyzer;
 pub mod cli;
+pub mod config;
 pub mod context;
+pub mod error;
 pub mod interpreter;
+pub mod native;
+pub mod span;
 pub mod standard_functions;
+pub mod streams;
 pub mod typechecker;
 pub mod types;
 
-use ::std::collections::HashMap;
 use ::std::env;
 use cli::*;
 use interpreter::*;
@@ -22,28 +27,22 @@ pub fn clear_terminal() {
     }
 }
 fn main() -> () {
-    let flags_map = parse_cmd_line_args();
+    let args: Vec<String> = env::args().skip(1).collect();
 
-    let flags = cli::Flags::new(flags_map);
+    let flags = match cli::Flags::parse(&args) {
+        Ok(flags) => flags,
+        Err(message) => {
+            eprintln!("{}\n\n{}", message, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
 
-    let file = format!("{}/{}", flags.proj_root, "scorch_src/main.scorch");
-
-    if flags.cli {
-        run_repl();
-    } else if flags.dump {
-        execute_file_then_dump(String::from(file));
-    } else {
-        execute_from_file(String::from(file));
-    }
-}
-fn parse_cmd_line_args() -> HashMap<String, bool> {
-    let mut flags = HashMap::new();
-    let args: Vec<String> = env::args().collect();
-    let mut i = 0;
-    while i < args.len() {
-        let arg = args[i].clone();
-        flags.insert(arg, true);
-        i += 1;
-    }
-    return flags;
+    let code = match config::run_on_stack(move || run_command(flags)) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("failed to start the interpreter : {}", err);
+            1
+        }
+    };
+    std::process::exit(code);
 }
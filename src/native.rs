@@ -0,0 +1,196 @@
+use super::error::{ErrorKind, ScorchError, ScorchResult};
+use super::types::{Function, Value};
+use scorch_parser::ast::*;
+use std::rc::Rc;
+
+// converts a scorch value into a rust argument for a native function.
+pub trait FromValue: Sized {
+    const TYPENAME: &'static str;
+    fn from_value(value: Value) -> Option<Self>;
+}
+
+// converts the return value of a native function back into a scorch value.
+pub trait IntoValue {
+    fn into_value(self) -> ScorchResult;
+}
+
+impl FromValue for Value {
+    const TYPENAME: &'static str = DYNAMIC_TNAME;
+    fn from_value(value: Value) -> Option<Self> {
+        Some(value)
+    }
+}
+impl FromValue for i64 {
+    const TYPENAME: &'static str = INT_TNAME;
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Int(val) => Some(val),
+            _ => None,
+        }
+    }
+}
+// ints that don't fit are rejected like any other mismatched argument.
+impl FromValue for i32 {
+    const TYPENAME: &'static str = INT_TNAME;
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Int(val) => i32::try_from(val).ok(),
+            _ => None,
+        }
+    }
+}
+impl FromValue for f64 {
+    const TYPENAME: &'static str = DOUBLE_TNAME;
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Double(val) => Some(val),
+            _ => None,
+        }
+    }
+}
+impl FromValue for bool {
+    const TYPENAME: &'static str = BOOL_TNAME;
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Bool(val) => Some(val),
+            _ => None,
+        }
+    }
+}
+impl FromValue for String {
+    const TYPENAME: &'static str = STRING_TNAME;
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::String(val) => Some(val),
+            _ => None,
+        }
+    }
+}
+impl FromValue for Rc<Function> {
+    const TYPENAME: &'static str = FN_TNAME;
+    fn from_value(value: Value) -> Option<Self> {
+        match value {
+            Value::Function(val) => Some(val),
+            _ => None,
+        }
+    }
+}
+
+impl IntoValue for Value {
+    fn into_value(self) -> ScorchResult {
+        Ok(self)
+    }
+}
+impl IntoValue for () {
+    fn into_value(self) -> ScorchResult {
+        Ok(Value::None())
+    }
+}
+impl IntoValue for i64 {
+    fn into_value(self) -> ScorchResult {
+        Ok(Value::Int(self))
+    }
+}
+impl IntoValue for i32 {
+    fn into_value(self) -> ScorchResult {
+        Ok(Value::Int(i64::from(self)))
+    }
+}
+impl IntoValue for f64 {
+    fn into_value(self) -> ScorchResult {
+        Ok(Value::Double(self))
+    }
+}
+impl IntoValue for bool {
+    fn into_value(self) -> ScorchResult {
+        Ok(Value::Bool(self))
+    }
+}
+impl IntoValue for String {
+    fn into_value(self) -> ScorchResult {
+        Ok(Value::String(self))
+    }
+}
+impl IntoValue for &str {
+    fn into_value(self) -> ScorchResult {
+        Ok(Value::String(self.to_string()))
+    }
+}
+impl IntoValue for Rc<Function> {
+    fn into_value(self) -> ScorchResult {
+        Ok(Value::Function(self))
+    }
+}
+impl<T: IntoValue> IntoValue for Option<T> {
+    fn into_value(self) -> ScorchResult {
+        match self {
+            Some(val) => val.into_value(),
+            None => Ok(Value::None()),
+        }
+    }
+}
+impl<T: IntoValue> IntoValue for ScorchResult<T> {
+    fn into_value(self) -> ScorchResult {
+        self?.into_value()
+    }
+}
+
+// implemented for rust closures & fns whose arguments and return value
+// can be converted, `Args` is a tuple of the argument types.
+pub trait NativeFn<Args> {
+    fn invoke(&mut self, name: &str, args: Vec<Value>) -> ScorchResult;
+    fn param_types(&self) -> Vec<&'static str>;
+}
+
+pub fn convert_arg<T: FromValue>(name: &str, index: usize, arg: Value) -> ScorchResult<T> {
+    let description = format!("{:?}", arg);
+    match T::from_value(arg) {
+        Some(val) => Ok(val),
+        None => Err(ScorchError::new(
+            ErrorKind::TypeMismatch,
+            format!("{name} expected argument {index} to be {}, got {description}", T::TYPENAME),
+        )
+        .with_subject(name)),
+    }
+}
+
+macro_rules! impl_native_fn {
+    ($($arg:ident),*) => {
+        impl<Func, Ret, $($arg),*> NativeFn<($($arg,)*)> for Func
+        where
+            Func: FnMut($($arg),*) -> Ret,
+            Ret: IntoValue,
+            $($arg: FromValue),*
+        {
+            #[allow(non_snake_case, unused_mut, unused_variables, unused_assignments)]
+            fn invoke(&mut self, name: &str, args: Vec<Value>) -> ScorchResult {
+                let arity = <[&str]>::len(&[$(stringify!($arg)),*]);
+                if args.len() != arity {
+                    return Err(ScorchError::new(
+                        ErrorKind::ArgumentCount,
+                        format!("{name} expected {arity} arguments, got {}", args.len()),
+                    )
+                    .with_subject(name));
+                }
+                let mut args = args.into_iter();
+                let mut index = 0;
+                $(
+                    let $arg = convert_arg::<$arg>(name, index, args.next().unwrap())?;
+                    index += 1;
+                )*
+                (self)($($arg),*).into_value()
+            }
+            fn param_types(&self) -> Vec<&'static str> {
+                vec![$($arg::TYPENAME),*]
+            }
+        }
+    };
+}
+
+impl_native_fn!();
+impl_native_fn!(A);
+impl_native_fn!(A, B);
+impl_native_fn!(A, B, C);
+impl_native_fn!(A, B, C, D);
+impl_native_fn!(A, B, C, D, E);
+impl_native_fn!(A, B, C, D, E, F);
@@ -0,0 +1,198 @@
+use super::error::SourceLocation;
+use scorch_parser::ast::Node;
+use scorch_parser::lexer::Token;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+
+// where each node of a parsed program came from. the ast doesn't carry positions, so they're
+// recorded after parsing by walking the tree in source order alongside the tokens it was parsed from.
+// nodes are keyed by address, a node that gets cloned needs its spans `copy`ed to the clone.
+// a table is only valid while its tree is alive, clones that outlive the tree get a table of their own.
+#[derive(Clone, Default)]
+pub struct Spans {
+    nodes: HashMap<*const Node, SourceLocation>,
+}
+
+impl Spans {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    // replaces whatever was recorded before, that tree has been run and dropped.
+    // create_tokenizer counts lines and columns from 1, and a token's position is its first character.
+    pub fn record(&mut self, source: &str, file: Option<&str>, tokens: &[Token], root: &Node) {
+        self.nodes.clear();
+        let lines: Vec<&str> = source.lines().collect();
+        let mut recorder = Recorder {
+            tokens,
+            lines: lines.iter().map(|line| line.chars().collect()).collect(),
+            cursor: 0,
+            spans: HashMap::new(),
+        };
+        recorder.visit(root);
+
+        for (node, span) in recorder.spans {
+            let Some((first, last)) = span else {
+                continue;
+            };
+            let (first, last) = (&tokens[first], &tokens[last]);
+            let length = if last.line == first.line {
+                last.column + last.value.chars().count() - first.column
+            } else {
+                first.value.chars().count()
+            };
+
+            self.nodes.insert(
+                node,
+                SourceLocation {
+                    file: file.map(|f| f.to_string()),
+                    line: first.line,
+                    column: first.column,
+                    length,
+                    source_line: lines.get(first.line - 1).unwrap_or(&"").trim_end().to_string(),
+                },
+            );
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+    pub fn location(&self, node: &Node) -> Option<&SourceLocation> {
+        self.nodes.get(&(node as *const Node))
+    }
+    // gives a clone of `from` the same spans, the two trees have the same shape.
+    pub fn copy(&mut self, from: &Node, to: &Node) {
+        let copied = self.copies(from, to);
+        self.insert(copied);
+    }
+    // gives a clone of `from` the spans `from` has in `source`.
+    pub fn copy_from(&mut self, source: &Spans, from: &Node, to: &Node) {
+        let copied = source.copies(from, to);
+        self.insert(copied);
+    }
+    fn copies(&self, from: &Node, to: &Node) -> Vec<(*const Node, Option<SourceLocation>)> {
+        let mut copied = vec![(to as *const Node, self.location(from).cloned())];
+        for (from, to) in children(from).into_iter().zip(children(to)) {
+            copied.extend(self.copies(from, to));
+        }
+        copied
+    }
+    fn insert(&mut self, copied: Vec<(*const Node, Option<SourceLocation>)>) {
+        for (node, location) in copied {
+            match location {
+                Some(location) => self.nodes.insert(node, location),
+                // the address may be reused from a tree that's since been dropped.
+                None => self.nodes.remove(&node),
+            };
+        }
+    }
+}
+
+struct Recorder<'a> {
+    tokens: &'a [Token],
+    lines: Vec<Vec<char>>,
+    // tokens before this belong to nodes already visited.
+    cursor: usize,
+    // the first and last token of each node, None if none of it could be matched.
+    spans: HashMap<*const Node, Option<(usize, usize)>>,
+}
+
+impl Recorder<'_> {
+    fn visit(&mut self, node: &Node) -> Option<(usize, usize)> {
+        let mut span = own_token(node).and_then(|name| self.next(name)).map(|i| (i, i));
+
+        for child in children(node) {
+            if let Some((first, last)) = self.visit(child) {
+                span = Some(match span {
+                    Some((f, l)) => (f.min(first), l.max(last)),
+                    None => (first, last),
+                });
+            }
+        }
+
+        self.spans.insert(node as *const Node, span);
+        span
+    }
+    // the index of the next unclaimed token that's this name where it's written in the source.
+    // literals aren't matched since the lexer may store them differently than they're written, only names are.
+    fn next(&mut self, name: &str) -> Option<usize> {
+        let offset = self.tokens[self.cursor..]
+            .iter()
+            .position(|token| token.value == name && self.is_name(token))?;
+        let index = self.cursor + offset;
+        self.cursor = index + 1;
+        Some(index)
+    }
+    // whether the token is written in the source as is, at its position. a string literal holding
+    // the same text is in quotes, so it's never taken for a name.
+    fn is_name(&self, token: &Token) -> bool {
+        let Some(line) = token.line.checked_sub(1).and_then(|line| self.lines.get(line)) else {
+            return false;
+        };
+        let Some(column) = token.column.checked_sub(1) else {
+            return false;
+        };
+        let length = token.value.chars().count();
+        let written = line.get(column..).map_or(false, |rest| rest.iter().copied().take(length).eq(token.value.chars()));
+        let quoted = column > 0 && matches!(line[column - 1], '\'' | '"');
+        written && !quoted
+    }
+}
+
+// the name a node is written with, if it has one.
+fn own_token(node: &Node) -> Option<&str> {
+    match node {
+        Node::Identifier(id)
+        | Node::DeclStmt { id, .. }
+        | Node::FunctionCall { id, .. }
+        | Node::FnDeclStmnt { id, .. }
+        | Node::ArrayAccessExpr { id, .. }
+        | Node::StructDecl { id, .. }
+        | Node::StructInit { id, .. } => Some(id.as_str()),
+        Node::TypeAssocBlock { typename, .. } => Some(typename.as_str()),
+        _ => None,
+    }
+}
+
+// child nodes in the order they appear in the source.
+fn children(node: &Node) -> Vec<&Node> {
+    fn all<T: Borrow<Node>>(nodes: &[T]) -> impl Iterator<Item = &Node> {
+        nodes.iter().map(|node| node.borrow())
+    }
+    fn opt<T: Borrow<Node>>(node: &Option<T>) -> impl Iterator<Item = &Node> {
+        node.iter().map(|node| node.borrow())
+    }
+
+    match node {
+        Node::Program(statements) => all(statements).collect(),
+        Node::Block(statements) => all(statements).collect(),
+        Node::DeclStmt { expression, .. } => vec![expression.as_ref()],
+        Node::AssignStmnt { id, expression } => vec![id.as_ref(), expression.as_ref()],
+        Node::Expression(inner) | Node::NotOp(inner) | Node::NegOp(inner) => vec![inner.as_ref()],
+        Node::BinaryOperation { lhs, rhs, .. }
+        | Node::RelationalExpression { lhs, rhs, .. }
+        | Node::LogicalExpression { lhs, rhs, .. } => vec![lhs.as_ref(), rhs.as_ref()],
+        Node::FunctionCall { arguments, .. } | Node::StructInit { arguments, .. } => {
+            arguments.iter().flat_map(|arguments| all(arguments)).collect()
+        }
+        Node::FnDeclStmnt { params, body, .. } => all(params).chain([body.as_ref()]).collect(),
+        Node::ParamDeclNode { varname, typename } => vec![varname.as_ref(), typename.as_ref()],
+        Node::IfStmnt { condition, block, else_stmnt } => {
+            [condition.as_ref(), block.as_ref()].into_iter().chain(opt(else_stmnt)).collect()
+        }
+        Node::ElseStmnt { condition, block, else_stmnt } => {
+            opt(condition).chain([block.as_ref()]).chain(opt(else_stmnt)).collect()
+        }
+        // the iterator id is written inside the condition too, so it's only matched there.
+        Node::RepeatStmnt { condition, block, .. } => opt(condition).chain([block.as_ref()]).collect(),
+        Node::ReturnStmnt(value) => opt(value).collect(),
+        Node::Array { elements, .. } => all(elements).collect(),
+        Node::ArrayAccessExpr { index_expr, expression, .. } => {
+            [index_expr.as_ref()].into_iter().chain(opt(expression)).collect()
+        }
+        Node::StructDecl { block, .. } | Node::TypeAssocBlock { block, .. } => vec![block.as_ref()],
+        _ => Vec::new(),
+    }
+}
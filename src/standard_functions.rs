@@ -1,24 +1,58 @@
-use super::types::Context;
-use super::typechecker::TypeChecker;
-use super::types::{Instance, Value};
+use super::config::Capability;
+use super::error::{ErrorKind, ScorchError, ScorchResult};
+use super::interpreter::Interpreter;
+use super::types::{Function, Instance, Value};
+use scorch_parser::ast::{ARRAY_TNAME, BOOL_TNAME, DOUBLE_TNAME, DYNAMIC_TNAME, FN_TNAME, INT_TNAME, NONE_TNAME, STRING_TNAME};
 use std::cell::RefCell;
+use std::io::{BufRead, Write};
 use std::process::Command;
 use std::{collections::HashMap, rc::Rc};
 
+// parameter & return types of a builtin, used by the static analyzer.
+#[derive(Clone, Debug)]
+pub struct Signature {
+    // none for variadic builtins, their arguments aren't checked.
+    pub params: Option<Vec<String>>,
+    pub return_type: String,
+}
+
+// builtins are reference counted so they can be called while the interpreter
+// is borrowed mutably, which lets them call back into scorch functions.
+#[derive(Clone)]
 pub struct StandardFunction {
-    pub func: Box<dyn FnMut(&mut Context, &TypeChecker, Vec<Value>) -> Value>,
+    pub func: Rc<dyn Fn(&mut Interpreter, Vec<Value>) -> ScorchResult>,
+    // the capability the interpreter's config has to grant for this to be callable.
+    pub capability: Option<Capability>,
+    pub signature: Signature,
 }
 impl StandardFunction {
-    pub fn new(func: Box<dyn FnMut(&mut Context, &TypeChecker, Vec<Value>) -> Value>) -> Self {
-        StandardFunction { func }
+    pub fn new(func: Box<dyn Fn(&mut Interpreter, Vec<Value>) -> ScorchResult>) -> Self {
+        StandardFunction {
+            func: Rc::from(func),
+            capability: None,
+            signature: Signature {
+                params: None,
+                return_type: DYNAMIC_TNAME.to_string(),
+            },
+        }
+    }
+    pub fn requires(mut self, capability: Capability) -> Self {
+        self.capability = Some(capability);
+        self
+    }
+    pub fn signature(mut self, params: &[&str], return_type: &str) -> Self {
+        self.signature = Signature {
+            params: Some(params.iter().map(|param| param.to_string()).collect()),
+            return_type: return_type.to_string(),
+        };
+        self
     }
-    pub fn call(
-        &mut self,
-        context: &mut Context,
-        type_checker: &TypeChecker,
-        args: Vec<Value>,
-    ) -> Value {
-        (self.func)(context, type_checker, args)
+    pub fn returns(mut self, return_type: &str) -> Self {
+        self.signature.return_type = return_type.to_string();
+        self
+    }
+    pub fn call(&self, interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
+        (self.func)(interpreter, args)
     }
 }
 
@@ -26,149 +60,252 @@ pub fn get_builtin_functions() -> HashMap<String, StandardFunction> {
     HashMap::from([
         (
             String::from("tochar"),
-            StandardFunction::new(Box::new(tochar)),
+            StandardFunction::new(Box::new(tochar)).signature(&[INT_TNAME], STRING_TNAME),
         ),
         (
             String::from("clearscreen"),
-            StandardFunction::new(Box::new(clear_screen)),
+            StandardFunction::new(Box::new(clear_screen))
+                .requires(Capability::TerminalControl)
+                .signature(&[], NONE_TNAME),
         ),
         (
             String::from("println"),
-            StandardFunction::new(Box::new(print_ln)),
+            StandardFunction::new(Box::new(print_ln)).returns(NONE_TNAME),
+        ),
+        (
+            String::from("eprintln"),
+            StandardFunction::new(Box::new(eprint_ln)).returns(NONE_TNAME),
         ),
         (
             String::from("readln"),
-            StandardFunction::new(Box::new(readln)),
+            StandardFunction::new(Box::new(readln))
+                .requires(Capability::ConsoleInput)
+                .signature(&[], STRING_TNAME),
+        ),
+        (
+            String::from("wait"),
+            StandardFunction::new(Box::new(wait))
+                .requires(Capability::Time)
+                .signature(&[DOUBLE_TNAME], NONE_TNAME),
         ),
-        (String::from("wait"), StandardFunction::new(Box::new(wait))),
         (
             String::from("tostr"),
-            StandardFunction::new(Box::new(tostr)),
+            StandardFunction::new(Box::new(tostr)).signature(&[DYNAMIC_TNAME], STRING_TNAME),
+        ),
+        (
+            String::from("time"),
+            StandardFunction::new(Box::new(time))
+                .requires(Capability::Time)
+                .signature(&[], INT_TNAME),
         ),
-        (String::from("time"), StandardFunction::new(Box::new(time))),
         (
             String::from("assert"),
-            StandardFunction::new(Box::new(assert)),
+            StandardFunction::new(Box::new(assert))
+                .signature(&[BOOL_TNAME, STRING_TNAME], NONE_TNAME),
         ),
         (
             String::from("assert_eq"),
-            StandardFunction::new(Box::new(assert_eq)),
+            StandardFunction::new(Box::new(assert_eq))
+                .signature(&[DYNAMIC_TNAME, DYNAMIC_TNAME, STRING_TNAME], NONE_TNAME),
         ),
         (String::from("find"), StandardFunction::new(Box::new(find))),
-        (String::from("len"), StandardFunction::new(Box::new(length))),
+        (
+            String::from("len"),
+            StandardFunction::new(Box::new(length))
+                .signature(&[ARRAY_TNAME], INT_TNAME),
+        ),
         (String::from("push"), StandardFunction::new(Box::new(push))),
-        (String::from("pop"), StandardFunction::new(Box::new(pop))),
+        (
+            String::from("pop"),
+            StandardFunction::new(Box::new(pop))
+                .signature(&[ARRAY_TNAME], DYNAMIC_TNAME),
+        ),
         (
             String::from("floor"),
-            StandardFunction::new(Box::new(floor)),
+            StandardFunction::new(Box::new(floor)).signature(&[DOUBLE_TNAME], DOUBLE_TNAME),
+        ),
+        (
+            String::from("abs"),
+            StandardFunction::new(Box::new(abs))
+                .signature(&[DYNAMIC_TNAME], DYNAMIC_TNAME),
+        ),
+        (
+            String::from("map"),
+            StandardFunction::new(Box::new(map))
+                .signature(&[ARRAY_TNAME, FN_TNAME], ARRAY_TNAME),
+        ),
+        (
+            String::from("filter"),
+            StandardFunction::new(Box::new(filter))
+                .signature(&[ARRAY_TNAME, FN_TNAME], ARRAY_TNAME),
+        ),
+        (
+            String::from("reduce"),
+            StandardFunction::new(Box::new(reduce))
+                .signature(&[ARRAY_TNAME, FN_TNAME, DYNAMIC_TNAME], DYNAMIC_TNAME),
+        ),
+        (
+            String::from("for_each"),
+            StandardFunction::new(Box::new(for_each))
+                .signature(&[ARRAY_TNAME, FN_TNAME], NONE_TNAME),
+        ),
+        (
+            String::from("sort_by"),
+            StandardFunction::new(Box::new(sort_by))
+                .signature(&[ARRAY_TNAME, FN_TNAME], ARRAY_TNAME),
+        ),
+        (
+            String::from("wrapping_add"),
+            StandardFunction::new(Box::new(wrapping_add)).signature(&[INT_TNAME, INT_TNAME], INT_TNAME),
+        ),
+        (
+            String::from("wrapping_sub"),
+            StandardFunction::new(Box::new(wrapping_sub)).signature(&[INT_TNAME, INT_TNAME], INT_TNAME),
+        ),
+        (
+            String::from("wrapping_mul"),
+            StandardFunction::new(Box::new(wrapping_mul)).signature(&[INT_TNAME, INT_TNAME], INT_TNAME),
+        ),
+        (
+            String::from("args"),
+            StandardFunction::new(Box::new(script_args)).signature(&[], ARRAY_TNAME),
+        ),
+        (
+            String::from("exit"),
+            StandardFunction::new(Box::new(exit))
+                .requires(Capability::Process)
+                .signature(&[INT_TNAME], NONE_TNAME),
         ),
-        (String::from("abs"), StandardFunction::new(Box::new(abs))),
     ])
 }
 
-pub fn clear_screen(_: &mut Context, _: &TypeChecker, _: Vec<Value>) -> Value {
+pub fn clear_screen(_: &mut Interpreter, _: Vec<Value>) -> ScorchResult {
     if cfg!(target_os = "windows") {
         let _ = Command::new("cmd").arg("/c").arg("cls").status();
     } else {
         let _ = Command::new("clear").status();
     }
-    Value::None()
+    Ok(Value::None())
 }
 
 // IO
-pub fn print_ln(context: &mut Context, type_checker: &TypeChecker, args: Vec<Value>) -> Value {
+fn format_for_print(arg: Value) -> ScorchResult<String> {
+    match arg {
+        Value::Return(_) => Err(ScorchError::new(ErrorKind::Internal, "Cannot print return value")),
+        _ => Ok(format!("{}\n", arg)),
+    }
+}
+fn write_to(stream: &mut dyn Write, text: &str) -> ScorchResult<()> {
+    stream
+        .write_all(text.as_bytes())
+        .and_then(|_| stream.flush())
+        .map_err(|err| ScorchError::new(ErrorKind::Io, format!("failed to write output : {}", err)))
+}
+pub fn print_ln(interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
     for arg in args {
-        match arg {
-            Value::Reference(..) => todo!(),
-            Value::Int(val) => print!("{}\n", val),
-            Value::Double(val) => print!("{}\n", val),
-            Value::Bool(val) => print!("{}\n", val),
-            Value::String(val) => print!("{}\n", val),
-            Value::None() => print!("{:?}", Value::None()),
-            Value::Function(_) => {
-                let newargs = Vec::from([arg]);
-                return tostr(context, type_checker, newargs);
-            }
-            Value::Array(mutable, elements) => {
-                let mutable_str = if mutable { "var" } else { "const" };
-
-                println!(
-                    "{} Array<T> : length {}",
-                    mutable_str,
-                    elements.borrow_mut().len()
-                );
-
-                // for element in elements.iter() {
-                //     print_ln(Vec::from([element.value.clone()]));
-                // }
-            }
-            Value::StructInstance {
-                typename: _name,
-                context: _,
-            } => {
-                // println!("global::{}\n{:#?}", name, context);
-                // for (k, member) in context.variables.iter() {
-                //     println!("{} : {:?}", k, member.value)
-                // }
-            }
-            Value::Return(_) => panic!("Cannot print return value"),
-        }
+        let text = format_for_print(arg)?;
+        write_to(&mut interpreter.stdout, &text)?;
     }
-    Value::None()
+    Ok(Value::None())
 }
-pub fn readln(_context: &mut Context, _type_checker: &TypeChecker, args: Vec<Value>) -> Value {
+pub fn eprint_ln(interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
+    for arg in args {
+        let text = format_for_print(arg)?;
+        write_to(&mut interpreter.stderr, &text)?;
+    }
+    Ok(Value::None())
+}
+pub fn readln(interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
     if args.len() != 0 {
-        panic!("readln expected 0 arguments");
+        return Err(ScorchError::new(ErrorKind::ArgumentCount, "readln expected 0 arguments"));
     }
     let mut input = String::new();
-    std::io::stdin()
-        .read_line(&mut input)
-        .expect("failed to read from stdin");
-    Value::String(input.replace("\n", ""))
+    if let Err(err) = interpreter.stdin.read_line(&mut input) {
+        return Err(ScorchError::new(
+            ErrorKind::Io,
+            format!("failed to read from stdin : {}", err),
+        ));
+    }
+    Ok(Value::String(input.replace("\n", "")))
 }
 // System
-pub fn time(_context: &mut Context, _type_checker: &TypeChecker, args: Vec<Value>) -> Value {
+pub fn time(_interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
     if args.len() != 0 {
-        panic!("time expected 0 arguments");
+        return Err(ScorchError::new(ErrorKind::ArgumentCount, "time expected 0 arguments"));
     }
 
-    let time = std::time::SystemTime::now()
-        .duration_since(std::time::UNIX_EPOCH)
-        .expect("Time went backwards");
+    let time = match std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH) {
+        Ok(time) => time,
+        Err(err) => {
+            return Err(ScorchError::new(
+                ErrorKind::Io,
+                format!("the system clock is set before 1970 : {}", err),
+            ))
+        }
+    };
 
-    Value::Int(time.as_millis() as i32)
+    let Ok(millis) = i64::try_from(time.as_millis()) else {
+        return Err(ScorchError::new(ErrorKind::Overflow, "time in milliseconds doesn't fit in an int"));
+    };
+    Ok(Value::Int(millis))
 }
-pub fn wait(_context: &mut Context, _type_checker: &TypeChecker, args: Vec<Value>) -> Value {
+pub fn wait(_interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
     if args.len() != 1 {
-        panic!("wait expected 1 argument :: ms wait duration");
+        return Err(ScorchError::new(
+            ErrorKind::ArgumentCount,
+            "wait expected 1 argument :: ms wait duration",
+        ));
     }
     if let Value::Double(val) = args[0] {
         std::thread::sleep(std::time::Duration::from_millis(val as u64));
     } else {
-        panic!("wait expected a <num>");
+        return Err(ScorchError::new(
+            ErrorKind::TypeMismatch,
+            format!("wait expected a <num>, got {:?}", args[0]),
+        ));
+    }
+    Ok(Value::None())
+}
+pub fn script_args(interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
+    if args.len() != 0 {
+        return Err(ScorchError::new(ErrorKind::ArgumentCount, "args expected 0 arguments"));
     }
-    Value::None()
+    let values = interpreter.script_args.iter().map(|arg| Value::String(arg.clone())).collect();
+    new_array(interpreter, false, values)
+}
+// unwinds the whole script, the host decides what to do with the code.
+pub fn exit(_interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
+    if args.len() != 1 {
+        return Err(ScorchError::new(ErrorKind::ArgumentCount, "exit expected 1 argument :: exit code"));
+    }
+    let Some(code) = args[0].as_int().and_then(|code| i32::try_from(*code).ok()) else {
+        return Err(ScorchError::new(
+            ErrorKind::TypeMismatch,
+            format!("exit expected an int exit code, got {:?}", args[0]),
+        ));
+    };
+    Err(ScorchError::new(ErrorKind::Exit(code), format!("exited with code {}", code)))
 }
 // Vectors & Arrays
-pub fn length(_context: &mut Context, _type_checker: &TypeChecker, args: Vec<Value>) -> Value {
+pub fn length(_interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
     if args.len() != 1 {
-        panic!("length takes one array argument");
+        return Err(ScorchError::new(ErrorKind::ArgumentCount, "length takes one array argument"));
     }
     let arg = &args[0];
 
     match arg {
         Value::Array(_, elements) => {
-            return Value::Int(elements.borrow_mut().len() as i32);
-        }
-        _ => {
-            dbg!(arg);
-            panic!("Cannot get length of value");
+            return Ok(Value::Int(elements.borrow_mut().len() as i64));
         }
+        _ => Err(ScorchError::new(
+            ErrorKind::TypeMismatch,
+            format!("Cannot get length of value {:?}", arg),
+        )),
     }
 }
-pub fn push(_context: &mut Context, type_checker: &TypeChecker, mut args: Vec<Value>) -> Value {
+pub fn push(interpreter: &mut Interpreter, mut args: Vec<Value>) -> ScorchResult {
     if args.len() < 2 {
-        panic!("push expected 2 arguments");
+        return Err(ScorchError::new(ErrorKind::ArgumentCount, "push expected 2 arguments"));
     }
     let arg = args.remove(0);
 
@@ -176,210 +313,367 @@ pub fn push(_context: &mut Context, type_checker: &TypeChecker, mut args: Vec<Va
         Value::Array(mutable, elements) => {
             if mutable {
                 for value in args {
-                    if let Some(t) = type_checker.from_value(&value) {
+                    if let Some(t) = interpreter.type_checker.from_value(&value) {
                         let var = Rc::new(RefCell::new(Instance::new(mutable, value, Rc::clone(&t))));
                         elements.borrow_mut().push(var);
                     } else {
-                        panic!("invalid type for array");
+                        return Err(ScorchError::new(
+                            ErrorKind::TypeMismatch,
+                            format!("invalid type for array : {:?}", value),
+                        ));
                     }
                 }
 
-                return Value::Array(mutable, elements);
+                return Ok(Value::Array(mutable, elements));
             } else {
-                panic!("Cannot push to immutable array");
+                Err(ScorchError::new(ErrorKind::Immutable, "Cannot push to immutable array"))
             }
         }
-        _ => {
-            panic!("Cannot push to value");
-        }
+        _ => Err(ScorchError::new(
+            ErrorKind::TypeMismatch,
+            format!("Cannot push to value {:?}", arg),
+        )),
     }
 }
-pub fn find(_context: &mut Context, _type_checker: &TypeChecker, mut args: Vec<Value>) -> Value {
+pub fn find(_interpreter: &mut Interpreter, mut args: Vec<Value>) -> ScorchResult {
     if args.len() < 2 {
-        panic!("find expects at least 2 arguments: a string key and an array or struct instance to search in. got : {:#?}", args);
+        return Err(ScorchError::new(
+            ErrorKind::ArgumentCount,
+            format!("find expects at least 2 arguments: a string key and an array or struct instance to search in. got : {:#?}", args),
+        ));
     }
     let key = args.pop().unwrap();
     let search_target = args.pop().unwrap();
-    
+
     match search_target {
         Value::Array(_, elements) => {
-            let key = key.as_string().unwrap();
-            
+            let Some(key) = key.as_string() else {
+                return Err(ScorchError::new(
+                    ErrorKind::TypeMismatch,
+                    format!("find expects a string key, got : {:?}", key),
+                ));
+            };
+
             for element in elements.borrow().iter() {
                 let element = element.borrow_mut();
-                let Value::StructInstance {typename: _, context } = &element.value else {
-                    panic!("find expects an array or struct instance as the search target, got : {:#?}", &element.value);
+                let Value::StructInstance { context, .. } = &element.value else {
+                    return Err(ScorchError::new(
+                        ErrorKind::TypeMismatch,
+                        format!("find expects an array or struct instance as the search target, got : {:#?}", &element.value),
+                    ));
                 };
-                
+
                 if let Some(member) = context.variables.get("key") {
                     let mem = member.borrow();
                     let string = mem.value.as_string();
                     let mut equals = false;
-                    
+
                     if let Some(string) = string {
                         equals = *string == *key;
                     };
-                    
+
                     if !equals {
                         continue;
                     }
-                    
-                    return Value::Reference(Rc::clone(&member));
+
+                    return Ok(Value::Reference(Rc::clone(&member)));
                 }
             }
         }
         _ => {
-            panic!("find expects an array or struct instance as the search target, got {:#?}", search_target);
+            return Err(ScorchError::new(
+                ErrorKind::TypeMismatch,
+                format!("find expects an array or struct instance as the search target, got {:#?}", search_target),
+            ));
         }
     }
-    
-    Value::None()
+
+    Ok(Value::None())
 }
-pub fn pop(_context: &mut Context, _type_checker: &TypeChecker, mut args: Vec<Value>) -> Value {
+pub fn pop(_interpreter: &mut Interpreter, mut args: Vec<Value>) -> ScorchResult {
     if args.len() != 1 {
-        panic!("pop expected 1 argument");
+        return Err(ScorchError::new(ErrorKind::ArgumentCount, "pop expected 1 argument"));
     }
     let arg = args.remove(0);
     match arg {
         Value::Array(mutable, elements) => {
             let mut el = elements.borrow_mut();
-            assert!(
-                el.len() > (0 as usize),
-                "stack underflow: cannot pop from an empty array."
-            );
-            assert!(mutable, "Cannot pop from immutable array");
-            
+            if !mutable {
+                return Err(ScorchError::new(ErrorKind::Immutable, "Cannot pop from immutable array"));
+            }
+
             let val = el.pop();
-            
+
             if let Some(val) = val {
-                return Value::Reference(Rc::clone(&val))
+                return Ok(Value::Reference(Rc::clone(&val)))
             } else {
-                panic!("Cannot pop from empty array");
+                Err(ScorchError::new(
+                    ErrorKind::IndexOutOfBounds,
+                    "stack underflow: cannot pop from an empty array.",
+                ))
             }
-            
+
         }
-        _ => {
-            panic!("Cannot pop from non-array value");
+        _ => Err(ScorchError::new(
+            ErrorKind::TypeMismatch,
+            format!("Cannot pop from non-array value {:?}", arg),
+        )),
+    }
+}
+// Higher order
+fn expect_array(name: &str, value: &Value) -> ScorchResult<(bool, Vec<Value>)> {
+    match value {
+        // copy the values out, so the callback is free to modify the array.
+        Value::Array(mutable, elements) => Ok((
+            *mutable,
+            elements.borrow().iter().map(|element| element.borrow().value.clone()).collect(),
+        )),
+        _ => Err(ScorchError::new(
+            ErrorKind::TypeMismatch,
+            format!("{name} expected an array, got {:?}", value),
+        )),
+    }
+}
+fn expect_function(name: &str, value: &Value) -> ScorchResult<Rc<Function>> {
+    match value {
+        Value::Function(func) => Ok(Rc::clone(func)),
+        _ => Err(ScorchError::new(
+            ErrorKind::TypeMismatch,
+            format!("{name} expected a function, got {:?}", value),
+        )),
+    }
+}
+fn new_array(interpreter: &Interpreter, mutable: bool, values: Vec<Value>) -> ScorchResult {
+    let mut elements = Vec::with_capacity(values.len());
+    for value in values {
+        let Some(t) = interpreter.type_checker.from_value(&value) else {
+            return Err(ScorchError::new(
+                ErrorKind::TypeMismatch,
+                format!("invalid type for array : {:?}", value),
+            ));
+        };
+        elements.push(Rc::new(RefCell::new(Instance::new(mutable, value, t))));
+    }
+    Ok(Value::Array(mutable, Rc::new(RefCell::new(elements))))
+}
+pub fn map(interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
+    if args.len() != 2 {
+        return Err(ScorchError::new(ErrorKind::ArgumentCount, "map expected 2 arguments :: array, fn"));
+    }
+    let (mutable, values) = expect_array("map", &args[0])?;
+    let func = expect_function("map", &args[1])?;
+
+    let mut results = Vec::with_capacity(values.len());
+    for value in values {
+        results.push(interpreter.call_function(&func, vec![value])?);
+    }
+    new_array(interpreter, mutable, results)
+}
+pub fn filter(interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
+    if args.len() != 2 {
+        return Err(ScorchError::new(ErrorKind::ArgumentCount, "filter expected 2 arguments :: array, fn"));
+    }
+    let (mutable, values) = expect_array("filter", &args[0])?;
+    let func = expect_function("filter", &args[1])?;
+
+    let mut results = Vec::new();
+    for value in values {
+        match interpreter.call_function(&func, vec![value.clone()])? {
+            Value::Bool(true) => results.push(value),
+            Value::Bool(false) => {}
+            other => {
+                return Err(ScorchError::new(
+                    ErrorKind::TypeMismatch,
+                    format!("filter expected its predicate to return a bool, got {:?}", other),
+                ))
+            }
         }
     }
+    new_array(interpreter, mutable, results)
 }
-// Testing
-pub fn assert_eq(_context: &mut Context, _type_checker: &TypeChecker, args: Vec<Value>) -> Value {
+pub fn reduce(interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
     if args.len() != 3 {
-        panic!("assert expected 3 arguments");
+        return Err(ScorchError::new(
+            ErrorKind::ArgumentCount,
+            "reduce expected 3 arguments :: array, fn, initial value",
+        ));
+    }
+    let (_, values) = expect_array("reduce", &args[0])?;
+    let func = expect_function("reduce", &args[1])?;
+
+    let mut accumulator = args[2].clone();
+    for value in values {
+        accumulator = interpreter.call_function(&func, vec![accumulator, value])?;
+    }
+    Ok(accumulator)
+}
+pub fn for_each(interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
+    if args.len() != 2 {
+        return Err(ScorchError::new(ErrorKind::ArgumentCount, "for_each expected 2 arguments :: array, fn"));
+    }
+    let (_, values) = expect_array("for_each", &args[0])?;
+    let func = expect_function("for_each", &args[1])?;
+
+    for value in values {
+        interpreter.call_function(&func, vec![value])?;
     }
-    let message = args[2].as_string().unwrap();
+    Ok(Value::None())
+}
+// returns a sorted copy of the array, the function decides whether its first argument goes before its second.
+pub fn sort_by(interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
+    if args.len() != 2 {
+        return Err(ScorchError::new(ErrorKind::ArgumentCount, "sort_by expected 2 arguments :: array, fn"));
+    }
+    let (mutable, values) = expect_array("sort_by", &args[0])?;
+    let func = expect_function("sort_by", &args[1])?;
 
-    let are_equal = match (&args[0], &args[1]) {
-        (Value::None(), Value::None()) => true,
-        (Value::Int(lhs_val), Value::Int(rhs_val)) => lhs_val == rhs_val,
-        (Value::Double(lhs_val), Value::Double(rhs_val)) => lhs_val == rhs_val,
-        (Value::Bool(lhs_val), Value::Bool(rhs_val)) => lhs_val == rhs_val,
-        (Value::String(lhs_val), Value::String(rhs_val)) => lhs_val == rhs_val,
-        _ => false,
+    // a stable insertion sort, a comparison can fail so we can't hand it to slice::sort_by.
+    let mut sorted: Vec<Value> = Vec::with_capacity(values.len());
+    for value in values {
+        let mut index = sorted.len();
+        while index > 0 {
+            let before = interpreter.call_function(&func, vec![value.clone(), sorted[index - 1].clone()])?;
+            match before {
+                Value::Bool(true) => index -= 1,
+                Value::Bool(false) => break,
+                other => {
+                    return Err(ScorchError::new(
+                        ErrorKind::TypeMismatch,
+                        format!("sort_by expected its comparison to return a bool, got {:?}", other),
+                    ))
+                }
+            }
+        }
+        sorted.insert(index, value);
+    }
+    new_array(interpreter, mutable, sorted)
+}
+// Testing
+pub fn assert_eq(_interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
+    if args.len() != 3 {
+        return Err(ScorchError::new(ErrorKind::ArgumentCount, "assert_eq expected 3 arguments"));
+    }
+    let Some(message) = args[2].as_string() else {
+        return Err(ScorchError::new(
+            ErrorKind::TypeMismatch,
+            format!("assert_eq expected a string message, got {:?}", args[2]),
+        ));
     };
-    assert!(are_equal, "{}", message);
-    Value::None()
+
+    if !args[0].deep_eq(&args[1]) {
+        return Err(ScorchError::new(
+            ErrorKind::AssertionFailed,
+            format!("{}\n    left  : {}\n    right : {}", message, args[0], args[1]),
+        ));
+    }
+    Ok(Value::None())
 }
-pub fn assert(_context: &mut Context, _type_checker: &TypeChecker, args: Vec<Value>) -> Value {
+pub fn assert(_interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
     if args.len() != 2 {
-        panic!("assert expected 2 or 3 arguments");
+        return Err(ScorchError::new(ErrorKind::ArgumentCount, "assert expected 2 arguments"));
     }
-    let condition = args[0].as_bool().unwrap();
-    let message = args[1].as_string().unwrap();
-    assert!(condition, "{}", message);
-    Value::None()
+    let (Some(condition), Some(message)) = (args[0].as_bool(), args[1].as_string()) else {
+        return Err(ScorchError::new(
+            ErrorKind::TypeMismatch,
+            format!("assert expected a bool and a string, got {:?} and {:?}", args[0], args[1]),
+        ));
+    };
+    if !condition {
+        return Err(ScorchError::new(ErrorKind::AssertionFailed, message.clone()));
+    }
+    Ok(Value::None())
 }
 // Conversions
-pub fn tostr(_context: &mut Context, _type_checker: &TypeChecker, args: Vec<Value>) -> Value {
+pub fn tostr(_interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
     if args.len() != 1 {
-        panic!("tostr expected 1 argument");
+        return Err(ScorchError::new(ErrorKind::ArgumentCount, "tostr expected 1 argument"));
     }
-    let arg = &args[0];
-    let result = match arg {
-        Value::Int(val) => val.to_string(),
-        Value::Double(val) => val.to_string(),
-        Value::String(val) => val.clone(),
-        Value::Bool(val) => val.to_string(),
-        Value::None() => String::from(scorch_parser::ast::NONE_TNAME),
-        Value::Function(func) => {
-            let stri = get_function_signature(func);
-            stri
-        }
-        Value::Array(mutable, elements) => {
-            let mutable_str = if *mutable { "mutable" } else { "immutable" };
-            format!(
-                "array : {} , length : {}",
-                mutable_str,
-                elements.borrow_mut().len()
-            )
-        }
-        _ => {
-            dbg!(arg);
-            dbg!(args);
-            panic!("Cannot convert value to string");
-        }
-    };
-    Value::String(result)
+    Ok(Value::String(args[0].to_string()))
 }
 pub fn get_function_signature<'ctx>(func: &'ctx Rc<super::types::Function>) -> String {
-    let params: Vec<String> = func
-        .params
-        .iter()
-        .map(|param| format!("{}: {}", param.name, param.m_type.borrow().name))
-        .collect();
-    format!(
-        "{}({}) -> {}",
-        func.name,
-        params.join(", "),
-        func.return_type.borrow().name
-    )
-}
-pub fn tochar(_context: &mut Context, _type_checker: &TypeChecker, args: Vec<Value>) -> Value {
+    func.to_string()
+}
+pub fn tochar(_interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
     if args.len() != 1 {
-        panic!("tochar requires a positive 'u32' integer argument");
+        return Err(ScorchError::new(
+            ErrorKind::ArgumentCount,
+            "tochar requires a positive 'u32' integer argument",
+        ));
     }
     let value = &args[0];
-    
+
     match value {
         Value::Int(v) => {
-            let char = char::from_u32(*v as u32);
-            
+            let char = u32::try_from(*v).ok().and_then(char::from_u32);
+
             match char {
                 Some(c) => {
                     let string = String::from(c);
-                    Value::String(string)
-                }
-                None => {
-                    panic!("failed to cast to char.");
+                    Ok(Value::String(string))
                 }
+                None => Err(ScorchError::new(
+                    ErrorKind::TypeMismatch,
+                    format!("failed to cast {} to char.", v),
+                )),
             }
         }
-        _ => {
-            panic!("unsupported type passed to 'tochar'");
-        }
+        _ => Err(ScorchError::new(
+            ErrorKind::TypeMismatch,
+            format!("unsupported type passed to 'tochar' : {:?}", value),
+        )),
     }
 }
 // Math
 // IO
-pub fn abs(_context: &mut Context, _type_checker: &TypeChecker, args: Vec<Value>) -> Value {
+pub fn abs(_interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
     if args.len() != 1 {
-        panic!("abs expected 1 argument");
+        return Err(ScorchError::new(ErrorKind::ArgumentCount, "abs expected 1 argument"));
     }
     let arg = &args[0];
     match arg {
-        Value::Int(val) => Value::Int(val.abs()),
-        Value::Double(val) => Value::Double(val.abs()),
-        _ => panic!("Cannot apply abs function to non-numeric value"),
+        Value::Int(val) => match val.checked_abs() {
+            Some(val) => Ok(Value::Int(val)),
+            None => Err(ScorchError::new(ErrorKind::Overflow, format!("integer overflow : abs({})", val))),
+        },
+        Value::Double(val) => Ok(Value::Double(val.abs())),
+        _ => Err(ScorchError::new(
+            ErrorKind::TypeMismatch,
+            format!("Cannot apply abs function to non-numeric value {:?}", arg),
+        )),
+    }
+}
+// int arithmetic is checked, these wrap around instead for when that's intended.
+pub fn wrapping_add(_interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
+    wrapping("wrapping_add", args, i64::wrapping_add)
+}
+pub fn wrapping_sub(_interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
+    wrapping("wrapping_sub", args, i64::wrapping_sub)
+}
+pub fn wrapping_mul(_interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
+    wrapping("wrapping_mul", args, i64::wrapping_mul)
+}
+fn wrapping(name: &str, args: Vec<Value>, op: fn(i64, i64) -> i64) -> ScorchResult {
+    if args.len() != 2 {
+        return Err(ScorchError::new(ErrorKind::ArgumentCount, format!("{} expected 2 arguments", name)));
     }
+    let (Some(lhs), Some(rhs)) = (args[0].as_int(), args[1].as_int()) else {
+        return Err(ScorchError::new(
+            ErrorKind::TypeMismatch,
+            format!("{} expected two ints, got {:?} and {:?}", name, args[0], args[1]),
+        ));
+    };
+    Ok(Value::Int(op(*lhs, *rhs)))
 }
-pub fn floor(_context: &mut Context, _type_checker: &TypeChecker, args: Vec<Value>) -> Value {
+pub fn floor(_interpreter: &mut Interpreter, args: Vec<Value>) -> ScorchResult {
     if args.len() != 1 {
-        panic!("floor expected 1 argument");
+        return Err(ScorchError::new(ErrorKind::ArgumentCount, "floor expected 1 argument"));
     }
     let arg = &args[0];
     match arg {
-        Value::Double(val) => Value::Double(val.floor()),
-        _ => panic!("Cannot apply floor function to non-double value"),
+        Value::Double(val) => Ok(Value::Double(val.floor())),
+        _ => Err(ScorchError::new(
+            ErrorKind::TypeMismatch,
+            format!("Cannot apply floor function to non-double value {:?}", arg),
+        )),
     }
 }
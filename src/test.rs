@@ -2,41 +2,669 @@ use crate::cli::execute_from_file;
 
 #[test]
 fn if_else_statements() {
-    execute_from_file(String::from("scorch_src/unit_tests/if_else.scorch"));
+    execute_from_file(String::from("scorch_src/unit_tests/if_else.scorch")).unwrap();
 }
 
 #[test]
 fn fields_vars_literals() {
     execute_from_file(String::from(
         "scorch_src/unit_tests/fields_vars_literals.scorch",
-    ));
+    )).unwrap();
 }
 
 #[test]
 fn loops() {
-    execute_from_file(String::from("scorch_src/unit_tests/loops.scorch"));
+    execute_from_file(String::from("scorch_src/unit_tests/loops.scorch")).unwrap();
 }
 
 #[test]
 fn structs() {
-    execute_from_file(String::from("scorch_src/unit_tests/structs.scorch"));
+    execute_from_file(String::from("scorch_src/unit_tests/structs.scorch")).unwrap();
 }
 
 #[test]
 fn functions() {
-    execute_from_file(String::from("scorch_src/unit_tests/functions.scorch"));
+    execute_from_file(String::from("scorch_src/unit_tests/functions.scorch")).unwrap();
 }
 
 #[test]
 fn relationals() {
-    execute_from_file(String::from("scorch_src/unit_tests/relationals.scorch"));
+    execute_from_file(String::from("scorch_src/unit_tests/relationals.scorch")).unwrap();
 }
 
 #[test]
 fn arithmetic() {
-    execute_from_file(String::from("scorch_src/unit_tests/arithmetic.scorch"));
+    execute_from_file(String::from("scorch_src/unit_tests/arithmetic.scorch")).unwrap();
 }
 #[test]
 fn arrays() {
-    execute_from_file(String::from("scorch_src/unit_tests/arrays.scorch"));
-}
\ No newline at end of file
+    execute_from_file(String::from("scorch_src/unit_tests/arrays.scorch")).unwrap();
+}
+#[test]
+fn closures() {
+    execute_from_file(String::from("scorch_src/unit_tests/closures.scorch")).unwrap();
+
+    // a callback field is printed like any other field, associated functions aren't.
+    let code = "f : int(x : int) {\n\treturn x\n}\n\
+                struct Button |\n\ton_click : fn = f\n|\n\
+                within Button {\n\tpress : int(this : Button) {\n\t\treturn this.on_click(1)\n\t}\n}\n\
+                tostr(new Button())";
+    let printed = crate::run(&String::from(code)).unwrap().to_string();
+    assert!(printed.contains("on_click") && !printed.contains("press"), "{}", printed);
+}
+#[test]
+fn anonymous_functions() {
+    use crate::interpreter::Interpreter;
+    use crate::types::Value;
+    use scorch_parser::ast::Node;
+
+    // no syntax produces a function without a name yet, so the tree is built by hand :
+    // `add_offset : fn = (x : int) -> int { return offset }`, with `offset` a global.
+    let identifier = |id: &str| Box::new(Node::Identifier(String::from(id)));
+    let body = Node::Block(vec![Box::new(Node::ReturnStmnt(Some(identifier("offset"))))]);
+    let function = Node::FnDeclStmnt {
+        id: String::new(),
+        params: vec![Node::ParamDeclNode {
+            varname: identifier("x"),
+            typename: identifier("int"),
+        }],
+        body: Box::new(body),
+        return_type: String::from("int"),
+        mutable: false,
+    };
+    let program = Node::Program(vec![Box::new(Node::DeclStmt {
+        target_type: String::from("fn"),
+        id: String::from("add_offset"),
+        expression: Box::new(function),
+        mutable: false,
+    })]);
+
+    let mut interpreter = Interpreter::new();
+    interpreter.set_global("offset", Value::Int(3)).unwrap();
+    program.accept(&mut interpreter).unwrap();
+
+    assert!(matches!(interpreter.get_global("add_offset"), Some(Value::Function(_))));
+    let returned = interpreter.call("add_offset", vec![Value::Int(1)]).unwrap();
+    assert!(matches!(returned, Value::Int(3)));
+}
+#[test]
+fn scopes_are_freed() {
+    use crate::interpreter::Interpreter;
+    use crate::types::Value;
+    use std::rc::Rc;
+
+    let mut interpreter = Interpreter::new();
+    let script = String::from(
+        "make_counter : fn() {
+            var count := 0
+            increment : int() {
+                count = count + 1
+                return count
+            }
+            return increment
+        }
+        struct Button |
+            on_click : fn = make_counter
+        |
+        button := new Button()",
+    );
+    crate::run_with_interpreter(&mut interpreter, &script).unwrap();
+
+    // a closure that escaped keeps its scope alive, until it's dropped itself.
+    let Value::Function(counter) = interpreter.call("make_counter", vec![]).unwrap() else {
+        panic!("make_counter failed to return a function");
+    };
+    let frame = Rc::downgrade(&counter.closure.scope().unwrap());
+    assert!(matches!(interpreter.call_value(Value::Function(Rc::clone(&counter)), vec![]), Ok(Value::Int(1))));
+    assert!(matches!(interpreter.call_value(Value::Function(Rc::clone(&counter)), vec![]), Ok(Value::Int(2))));
+    drop(counter);
+    assert!(frame.upgrade().is_none(), "make_counter's frame outlived the closure returned from it");
+
+    // top level functions, also when held by a field, don't keep the interpreter's scope alive.
+    let root = Rc::downgrade(&interpreter.context);
+    drop(interpreter);
+    assert!(root.upgrade().is_none(), "the top level scope outlived the interpreter");
+}
+#[test]
+fn lexical_scoping() {
+    use crate::error::ErrorKind;
+
+    // a function can't see the locals of whoever calls it.
+    let code = "reader : int() {\n\treturn secret\n}\ncaller : int() {\n\tsecret := 1\n\treturn reader()\n}\ncaller()";
+    assert_eq!(crate::run(&String::from(code)).unwrap_err().kind, ErrorKind::UndefinedVariable);
+
+    // nor do its locals leak into the caller.
+    let code = "setup : none() {\n\tleaked := 1\n}\nsetup()\nleaked";
+    assert_eq!(crate::run(&String::from(code)).unwrap_err().kind, ErrorKind::UndefinedVariable);
+
+    // globals declared after the function are still visible when it's called.
+    let code = "get : int() {\n\treturn later\n}\nlater := 7\nget()";
+    assert!(matches!(crate::run(&String::from(code)).unwrap(), crate::types::Value::Int(7)));
+}
+#[test]
+fn operators() {
+    execute_from_file(String::from("scorch_src/unit_tests/operators.scorch")).unwrap();
+}
+#[test]
+fn higher_order() {
+    execute_from_file(String::from("scorch_src/unit_tests/higher_order.scorch")).unwrap();
+}
+// runs a fixture under scorch_src/unit_tests/errors, which is expected to fail.
+fn run_failing(name: &str) -> crate::error::ScorchError {
+    execute_from_file(format!("scorch_src/unit_tests/errors/{}.scorch", name)).unwrap_err()
+}
+#[test]
+fn failed_assert() {
+    assert_eq!(run_failing("failed_assert").kind, crate::error::ErrorKind::AssertionFailed);
+}
+#[test]
+fn undefined_variable() {
+    assert_eq!(run_failing("undefined_variable").kind, crate::error::ErrorKind::UndefinedVariable);
+}
+#[test]
+fn assert_eq_shows_both_sides() {
+    let err = run_failing("assert_eq_arrays");
+    assert_eq!(err.kind, crate::error::ErrorKind::AssertionFailed);
+    assert_eq!(err.message, "arrays differ\n    left  : [1, 2]\n    right : [1, 3]");
+}
+#[test]
+fn operator_arity() {
+    let err = run_failing("operator_arity");
+    assert_eq!(err.kind, crate::error::ErrorKind::ArgumentCount);
+    assert_eq!(err.location.unwrap().line, 5);
+
+    // the other operators in the block are still registered.
+    let mut interpreter = crate::interpreter::Interpreter::new();
+    let code = crate::cli::read_source_file(&String::from("scorch_src/unit_tests/errors/operator_arity.scorch")).unwrap();
+    assert!(crate::run_with_interpreter(&mut interpreter, &code).is_err());
+    let difference = crate::run_with_interpreter(&mut interpreter, &String::from("new P(5) - new P(2)")).unwrap();
+    assert!(matches!(difference, crate::types::Value::Int(3)));
+}
+#[test]
+fn constructor_extra_argument() {
+    assert_eq!(run_failing("constructor_extra_argument").kind, crate::error::ErrorKind::ArgumentCount);
+}
+#[test]
+fn constructor_wrong_type() {
+    assert_eq!(run_failing("constructor_wrong_type").kind, crate::error::ErrorKind::TypeMismatch);
+}
+#[test]
+fn constructor_unknown_field() {
+    assert_eq!(run_failing("constructor_unknown_field").kind, crate::error::ErrorKind::UndefinedVariable);
+}
+#[test]
+fn constructor_field_twice() {
+    assert_eq!(run_failing("constructor_field_twice").kind, crate::error::ErrorKind::Redefinition);
+}
+#[test]
+fn not_callable() {
+    assert_eq!(run_failing("not_callable").kind, crate::error::ErrorKind::TypeMismatch);
+}
+#[test]
+fn wrong_return_type() {
+    let err = run_failing("wrong_return");
+    assert_eq!(err.kind, crate::error::ErrorKind::TypeMismatch);
+    assert_eq!(err.message, "f should return int, but returned string (hi)");
+}
+#[test]
+fn missing_return() {
+    assert_eq!(run_failing("missing_return").kind, crate::error::ErrorKind::TypeMismatch);
+}
+
+#[test]
+fn nominal_struct_types() {
+    use crate::error::ErrorKind;
+
+    let structs = "struct Player |\n\tname := 'p1'\n|\nstruct Enemy |\n\tname := 'e1'\n|\n\
+                   greet : string(p : Player) {\n\treturn p.name\n}\n";
+    let run = |code: &str| crate::run(&format!("{}{}", structs, code));
+
+    for code in [
+        "greet(1)",
+        "greet(new Enemy())",
+        "p : Player = new Enemy()",
+        "var p : Player = new Player()\np = 'player'",
+        "players := [new Player()]\nplayers[0] = new Enemy()",
+    ] {
+        assert_eq!(run(code).unwrap_err().kind, ErrorKind::TypeMismatch, "{}", code);
+    }
+
+    assert!(run("greet(new Player())").is_ok());
+    assert!(run("var anything : dynamic = new Player()\nanything = new Enemy()").is_ok());
+}
+
+#[test]
+fn error_locations() {
+    let code = String::from("x := 1\n\nf : int() {\n\treturn missing + 1\n}\ny := f()");
+    let err = crate::run(&code).unwrap_err();
+    let location = err.location.clone().unwrap();
+    assert_eq!((location.line, location.column), (4, 9));
+    assert_eq!(location.source_line, "\treturn missing + 1");
+    assert!(err.to_string().contains("4 | \treturn missing + 1\n  | \t       ^^^^^^^"));
+
+    let err = crate::run(&String::from("a := 1\nb := a + c")).unwrap_err();
+    let location = err.location.unwrap();
+    assert_eq!((location.line, location.column, location.length), (2, 10, 1));
+
+    // the failing expression is found, not the first mention of its names.
+    let err = crate::run(&String::from("a := 1\nb := 0\nprintln(a)\nprintln(a / b)")).unwrap_err();
+    let location = err.location.unwrap();
+    assert_eq!((location.line, location.column, location.length), (4, 9, 5));
+
+    // a string with the same text as a name isn't mistaken for it.
+    let err = crate::run(&String::from("x := 'missing'\ny := missing")).unwrap_err();
+    let location = err.location.unwrap();
+    assert_eq!((location.line, location.column), (2, 6));
+
+    // a function keeps the locations of its body after the program that declared it is gone.
+    let mut interpreter = crate::interpreter::Interpreter::new();
+    crate::run_with_interpreter(&mut interpreter, &String::from("f : int() {\n\treturn missing\n}")).unwrap();
+    let err = crate::run_with_interpreter(&mut interpreter, &String::from("x := 1\ny := f()")).unwrap_err();
+    let location = err.location.unwrap();
+    assert_eq!((location.line, location.source_line.as_str()), (2, "\treturn missing"));
+
+    // and declaring functions over and over doesn't add to the program's spans.
+    let declare = |times: usize| {
+        let mut interpreter = crate::interpreter::Interpreter::new();
+        let code = format!("var i := 0\nrepeat i < {} {{\n\tg : int() {{\n\t\treturn i\n\t}}\n\tg()\n}}", times);
+        crate::run_with_interpreter(&mut interpreter, &code).unwrap();
+        interpreter.spans.len()
+    };
+    assert_eq!(declare(1), declare(50));
+}
+
+#[test]
+fn call_stack_traces() {
+    let code = String::from(
+        "struct Counter |\n\tcount := 0\n|\n\
+         within Counter {\n\tcheck : none(this : Counter) {\n\t\tassert(false, 'bad counter')\n\t}\n}\n\
+         outer : none(c : Counter) {\n\tc.check()\n}\n\
+         counter := new Counter()\n\
+         outer(counter)",
+    );
+    let err = crate::run(&code).unwrap_err();
+    assert_eq!(err.kind, crate::error::ErrorKind::AssertionFailed);
+
+    let frames: Vec<(&str, usize)> = err
+        .trace
+        .iter()
+        .map(|frame| (frame.function.as_str(), frame.call_site.as_ref().unwrap().line))
+        .collect();
+    assert_eq!(frames, vec![("Counter.check", 10), ("outer", 13)]);
+
+    // each call site is the call that was running, not the first call to the same function.
+    let err = crate::run(&String::from("f : int(x : int) {\n\treturn 10 / x\n}\na := f(1)\nb := f(0)")).unwrap_err();
+    assert_eq!(err.trace[0].call_site.as_ref().unwrap().line, 5);
+    assert_eq!(err.location.unwrap().line, 6);
+}
+
+#[test]
+fn static_analysis() {
+    use crate::error::ErrorKind;
+
+    let code = String::from(
+        "x : int = 'one'\n\
+         f : int(a : int) {\n    return a\n}\n\
+         y := f(1, 2)\n\
+         z := missing\n\
+         if false {\n    w := f('no')\n}",
+    );
+    let diagnostics = crate::check(&code);
+    let kinds: Vec<ErrorKind> = diagnostics.iter().map(|err| err.kind).collect();
+    assert_eq!(
+        kinds,
+        vec![ErrorKind::TypeMismatch, ErrorKind::ArgumentCount, ErrorKind::UndefinedVariable, ErrorKind::TypeMismatch]
+    );
+    // branches that never run are checked too.
+    assert_eq!(diagnostics[3].location.as_ref().unwrap().line, 8);
+
+    let wrong_return = crate::check(&String::from("g : string() {\n    return 1\n}"));
+    assert_eq!(wrong_return[0].kind, ErrorKind::TypeMismatch);
+
+    assert!(crate::check(&String::from("x := 1 + 2\nprintln(tostr(x))")).is_empty());
+
+    // methods can call each other by name inside their within block.
+    let siblings = "struct P |\n\tx := 1\n|\nwithin P {\n\ta : int(this : P) {\n\t\treturn b(this)\n\t}\n\tb : int(this : P) {\n\t\treturn this.x\n\t}\n}";
+    assert!(crate::check(&String::from(siblings)).is_empty());
+
+    // like at runtime, a local can't shadow a global, but a field can have a global's name.
+    let shadowed = crate::check(&String::from("x := 1\nf : int() {\n\tx := 2\n\treturn x\n}"));
+    assert_eq!(shadowed.iter().map(|err| err.kind).collect::<Vec<_>>(), vec![ErrorKind::Redefinition]);
+    assert!(crate::check(&String::from("x := 1\nstruct P |\n\tx := 0\n|")).is_empty());
+}
+
+#[test]
+fn numeric_operators() {
+    use crate::error::ErrorKind;
+    use crate::types::Value;
+
+    let int = |code: &str| match crate::run(&String::from(code)).unwrap() {
+        Value::Int(value) => value,
+        other => panic!("expected an int, got {:?}", other),
+    };
+    assert_eq!(int("17 % 5"), 2);
+    assert_eq!(int("2 ** 10"), 1024);
+    assert_eq!(int("12 & 10"), 8);
+    assert_eq!(int("12 | 10"), 14);
+    assert_eq!(int("12 ^ 10"), 6);
+    assert_eq!(int("1 << 4"), 16);
+    assert_eq!(int("256 >> 4"), 16);
+
+    let mixed = crate::run(&String::from("2 ** 0.5")).unwrap();
+    assert!(matches!(mixed, Value::Double(value) if (value - 2f64.sqrt()).abs() < 1e-12));
+
+    for code in ["1 / 0", "1 % 0", "1.5 / 0"] {
+        let err = crate::run(&String::from(code)).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::DivisionByZero);
+    }
+    assert_eq!(crate::run(&String::from("1 << 64")).unwrap_err().kind, ErrorKind::InvalidOperator);
+}
+
+#[test]
+fn integer_overflow() {
+    use crate::error::ErrorKind;
+    use crate::types::Value;
+
+    let int = |code: &str| match crate::run(&String::from(code)).unwrap() {
+        Value::Int(value) => value,
+        other => panic!("expected an int, got {:?}", other),
+    };
+    assert_eq!(int("1 << 40"), 1 << 40);
+    assert_eq!(int("2 ** 62"), 1 << 62);
+    assert!(int("time()") > i64::from(i32::MAX));
+
+    for code in ["big := 2 ** 62\nbig * 2", "big := 2 ** 62\nbig + big", "-(2 ** 63)", "2 ** 64"] {
+        let err = crate::run(&String::from(code)).unwrap_err();
+        assert_eq!(err.kind, ErrorKind::Overflow, "{}", code);
+    }
+
+    assert_eq!(int("big := 2 ** 62\nwrapping_mul(big, 4)"), 0);
+    assert_eq!(int("wrapping_add(-(2 ** 62), -(2 ** 62))"), i64::MIN);
+}
+
+#[test]
+fn arrays_of_variables() {
+    use crate::types::Value;
+
+    let code = "f : int() {\n\treturn 1\n}\nfs := [f, f]\nlen(fs)";
+    assert!(matches!(crate::run(&String::from(code)).unwrap(), Value::Int(2)));
+
+    // elements are copies, reassigning the variable doesn't change the array.
+    let code = "var x := 1\nxs := [x]\nx = 2\nxs[0]";
+    assert!(matches!(crate::run(&String::from(code)).unwrap(), Value::Int(1)));
+}
+
+#[test]
+fn run_returns_program_value() {
+    use crate::types::Value;
+
+    let last_expression = crate::run(&String::from("x := 5\nx + 2")).unwrap();
+    assert!(matches!(last_expression, Value::Int(7)));
+
+    let returned = crate::run(&String::from("return 'done'\n10")).unwrap();
+    assert!(matches!(returned, Value::String(s) if s == "done"));
+
+    let parse_error = crate::run(&String::from("x := (1 + 2"));
+    assert_eq!(parse_error.unwrap_err().kind, crate::error::ErrorKind::Parse);
+}
+
+#[test]
+fn native_functions() {
+    use crate::error::ErrorKind;
+    use crate::interpreter::Interpreter;
+    use crate::types::Value;
+
+    let mut interpreter = Interpreter::new();
+    interpreter.register_fn("scale", |value: i32, factor: f64| -> f64 { value as f64 * factor });
+    interpreter.register_fn("greet", |name: String| format!("hello, {}", name));
+
+    let scaled = crate::run_with_interpreter(&mut interpreter, &String::from("scale(4, 0.5)")).unwrap();
+    assert!(matches!(scaled, Value::Double(v) if v == 2.0));
+
+    let greeting = crate::run_with_interpreter(&mut interpreter, &String::from("greet('scorch')")).unwrap();
+    assert!(matches!(greeting, Value::String(s) if s == "hello, scorch"));
+
+    let arity = crate::run_with_interpreter(&mut interpreter, &String::from("scale(4)"));
+    assert_eq!(arity.unwrap_err().kind, ErrorKind::ArgumentCount);
+
+    let mismatch = crate::run_with_interpreter(&mut interpreter, &String::from("greet(10)"));
+    assert_eq!(mismatch.unwrap_err().kind, ErrorKind::TypeMismatch);
+
+    // closures that keep state between calls can be registered too.
+    let mut calls = 0;
+    interpreter.register_fn("next_id", move || -> i32 {
+        calls += 1;
+        calls
+    });
+    let id = crate::run_with_interpreter(&mut interpreter, &String::from("next_id()\nnext_id()")).unwrap();
+    assert!(matches!(id, Value::Int(2)));
+}
+
+#[test]
+fn host_calls_and_globals() {
+    use crate::error::ErrorKind;
+    use crate::interpreter::Interpreter;
+    use crate::types::Value;
+
+    let mut interpreter = Interpreter::new();
+    let script = String::from(
+        "var score : double = 0.0
+        update : double(dt : double) {
+            score = score + dt
+            return score
+        }",
+    );
+    crate::run_with_interpreter(&mut interpreter, &script).unwrap();
+
+    let returned = interpreter.call("update", vec![Value::Double(0.5)]).unwrap();
+    assert!(matches!(returned, Value::Double(v) if v == 0.5));
+    assert!(matches!(interpreter.get_global("score"), Some(Value::Double(v)) if v == 0.5));
+
+    interpreter.set_global("score", Value::Double(10.0)).unwrap();
+    interpreter.call("update", vec![Value::Double(1.0)]).unwrap();
+    assert!(matches!(interpreter.get_global("score"), Some(Value::Double(v)) if v == 11.0));
+
+    let mismatch = interpreter.set_global("score", Value::String(String::from("high")));
+    assert_eq!(mismatch.unwrap_err().kind, ErrorKind::TypeMismatch);
+
+    interpreter.set_global("lives", Value::Int(3)).unwrap();
+    assert!(matches!(interpreter.get_global("lives"), Some(Value::Int(3))));
+
+    let missing = interpreter.call("render", vec![]);
+    assert_eq!(missing.unwrap_err().kind, ErrorKind::UndefinedFunction);
+
+    // a failed assignment leaves the variable as it was.
+    crate::run_with_interpreter(&mut interpreter, &String::from("var x : int = 1
+scores := [1, 2]")).unwrap();
+    let result = crate::run_with_interpreter(&mut interpreter, &String::from("x = 'hi'"));
+    assert_eq!(result.unwrap_err().kind, ErrorKind::TypeMismatch);
+    assert!(matches!(interpreter.get_global("x"), Some(Value::Int(1))));
+
+    let result = crate::run_with_interpreter(&mut interpreter, &String::from("scores[0] = 'hi'"));
+    assert_eq!(result.unwrap_err().kind, ErrorKind::TypeMismatch);
+    let result = crate::run_with_interpreter(&mut interpreter, &String::from("scores[0]"));
+    assert!(matches!(result, Ok(Value::Int(1))));
+}
+
+#[test]
+fn execution_limits() {
+    use crate::config::InterpreterConfig;
+    use crate::error::ErrorKind;
+    use crate::interpreter::Interpreter;
+    use std::time::Duration;
+
+    let endless = String::from("var i := 0\nrepeat {\n i = i + 1\n}");
+
+    let mut interpreter = Interpreter::with_config(InterpreterConfig {
+        max_steps: Some(10_000),
+        ..Default::default()
+    });
+    let result = crate::run_with_interpreter(&mut interpreter, &endless);
+    assert_eq!(result.unwrap_err().kind, ErrorKind::StepLimitExceeded);
+
+    let mut interpreter = Interpreter::with_config(InterpreterConfig {
+        timeout: Some(Duration::from_millis(50)),
+        ..Default::default()
+    });
+    let result = crate::run_with_interpreter(&mut interpreter, &endless);
+    assert_eq!(result.unwrap_err().kind, ErrorKind::Timeout);
+
+    let mut interpreter = Interpreter::with_config(InterpreterConfig {
+        max_call_depth: Some(64),
+        ..Default::default()
+    });
+    let recursion = String::from("f : int(n : int) {\n return f(n + 1)\n}\nf(0)");
+    let result = crate::run_with_interpreter(&mut interpreter, &recursion);
+    assert_eq!(result.unwrap_err().kind, ErrorKind::CallDepthExceeded);
+}
+
+#[test]
+fn default_call_depth_fits_its_stack() {
+    use crate::config::run_on_stack;
+    use crate::error::ErrorKind;
+    use crate::types::Value;
+
+    let depths = run_on_stack(|| {
+        // ordinary recursion a thousand calls deep.
+        let sum = String::from("sum : int(n : int) {\n\tif n == 0 {\n\t\treturn 0\n\t}\n\treturn n + sum(n - 1)\n}\nsum(1000)");
+        let sum = matches!(crate::run(&sum), Ok(Value::Int(500500)));
+
+        // runaway recursion hits the limit before the stack runs out.
+        let recursion = String::from("f : int(n : int) {\n\treturn 1 + f(n + 1) * 2\n}\nf(0)");
+        let recursion = crate::run(&recursion).unwrap_err().kind;
+
+        let methods = String::from(
+            "struct Node |\n\tdepth := 0\n|\n\
+             within Node {\n\tdeeper : int(this : Node) {\n\t\tnext := new Node(this.depth + 1)\n\t\treturn next.deeper() + 1\n\t}\n}\n\
+             root := new Node()\n\
+             root.deeper()",
+        );
+        let methods = crate::run(&methods).unwrap_err().kind;
+        (sum, recursion, methods)
+    })
+    .unwrap();
+
+    assert!(depths.0, "sum(1000) failed to return 500500");
+    assert_eq!(depths.1, ErrorKind::CallDepthExceeded);
+    assert_eq!(depths.2, ErrorKind::CallDepthExceeded);
+}
+
+#[test]
+fn capabilities() {
+    use crate::config::{Capability, InterpreterConfig};
+    use crate::error::ErrorKind;
+    use crate::interpreter::Interpreter;
+
+    let mut interpreter = Interpreter::with_config(InterpreterConfig::sandboxed());
+    let result = crate::run_with_interpreter(&mut interpreter, &String::from("readln()"));
+    assert_eq!(result.unwrap_err().kind, ErrorKind::PermissionDenied);
+
+    let result = crate::run_with_interpreter(&mut interpreter, &String::from("wait(1.0)"));
+    assert_eq!(result.unwrap_err().kind, ErrorKind::PermissionDenied);
+
+    // builtins without side effects stay available.
+    crate::run_with_interpreter(&mut interpreter, &String::from("x := abs(-1)")).unwrap();
+
+    let mut config = InterpreterConfig::sandboxed();
+    config.capabilities.insert(Capability::Time);
+    let mut interpreter = Interpreter::with_config(config);
+    crate::run_with_interpreter(&mut interpreter, &String::from("wait(1.0)")).unwrap();
+}
+
+#[test]
+fn captured_streams() {
+    use crate::interpreter::Interpreter;
+    use crate::streams::SharedBuffer;
+    use std::io::Cursor;
+
+    let stdout = SharedBuffer::new();
+    let stderr = SharedBuffer::new();
+
+    let mut interpreter = Interpreter::new();
+    interpreter.stdout = Box::new(stdout.clone());
+    interpreter.stderr = Box::new(stderr.clone());
+    interpreter.stdin = Box::new(Cursor::new("scorch\n"));
+
+    let script = String::from("name := readln()\nprintln('hello, ' + name)\nprintln(5)\neprintln('oops')");
+    crate::run_with_interpreter(&mut interpreter, &script).unwrap();
+
+    assert_eq!(stdout.contents(), "hello, scorch\n5\n");
+    assert_eq!(stderr.contents(), "oops\n");
+}
+
+#[test]
+fn value_printing() {
+    use crate::interpreter::Interpreter;
+    use crate::streams::SharedBuffer;
+
+    let stdout = SharedBuffer::new();
+    let mut interpreter = Interpreter::new();
+    interpreter.stdout = Box::new(stdout.clone());
+
+    let script = String::from(
+        "nums := [1, 2, [3]]
+        println(nums)
+        struct Point |
+            x := 1
+            label := 'origin'
+        |
+        p := new Point()
+        println(p)
+        var cyclic := [1]
+        push(cyclic, cyclic)
+        println(cyclic)
+        println(tostr(nums) == '[1, 2, [3]]')",
+    );
+    crate::run_with_interpreter(&mut interpreter, &script).unwrap();
+
+    assert_eq!(
+        stdout.contents(),
+        "[1, 2, [3]]\nPoint { x: 1, label: 'origin' }\n[1, [...]]\ntrue\n"
+    );
+}
+
+#[test]
+fn command_line() {
+    use crate::cli::{Command, Flags};
+    use crate::error::ErrorKind;
+    use crate::interpreter::Interpreter;
+    use crate::types::Value;
+
+    let to_args = |line: &str| line.split_whitespace().map(String::from).collect::<Vec<_>>();
+
+    let flags = Flags::parse(&to_args("--max-steps=100 run game.scorch -- --level 2")).unwrap();
+    assert!(matches!(&flags.command, Command::Run(file) if file == "game.scorch"));
+    assert_eq!(flags.script_args, vec!["--level", "2"]);
+    assert_eq!(flags.config().max_steps, Some(100));
+
+    let flags = Flags::parse(&to_args("--sandbox check game.scorch")).unwrap();
+    assert!(matches!(flags.command, Command::Check(_)));
+    assert!(flags.config().capabilities.is_empty());
+
+    let flags = Flags::parse(&to_args("no-interpret game.scorch")).unwrap();
+    assert!(matches!(&flags.command, Command::Check(file) if file == "game.scorch"));
+    let flags = Flags::parse(&to_args("no-interpret")).unwrap();
+    assert!(matches!(&flags.command, Command::Check(file) if file.ends_with("scorch_src/main.scorch")));
+    let flags = Flags::parse(&to_args("--no-interpret run game.scorch")).unwrap();
+    assert!(matches!(&flags.command, Command::Check(file) if file == "game.scorch"));
+    assert!(Flags::parse(&to_args("--no-interpret repl")).is_err());
+
+    assert!(Flags::parse(&to_args("run")).is_err());
+    assert!(Flags::parse(&to_args("--verbose repl")).is_err());
+
+    // dump runs the script with the same limits as run.
+    let flags = Flags::parse(&to_args("--max-steps 10 dump scorch_src/unit_tests/loops.scorch")).unwrap();
+    let Command::Dump(file) = &flags.command else {
+        panic!("dump failed to parse");
+    };
+    let dumped = crate::cli::execute_file_then_dump(file.clone(), flags.config());
+    assert_eq!(dumped.unwrap_err().kind, ErrorKind::StepLimitExceeded);
+
+    let mut interpreter = Interpreter::new();
+    interpreter.script_args = vec![String::from("first")];
+    let first = crate::run_with_interpreter(&mut interpreter, &String::from("script_args := args()\nscript_args[0]")).unwrap();
+    assert!(matches!(first, Value::String(s) if s == "first"));
+
+    let exited = crate::run_with_interpreter(&mut interpreter, &String::from("exit(3)\nassert(false, 'unreachable')"));
+    assert_eq!(exited.unwrap_err().kind, ErrorKind::Exit(3));
+}
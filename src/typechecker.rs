@@ -1,6 +1,7 @@
-use crate::{types::Value, context::Context};
+use crate::{types::{Closure, Value}, context::Context, span::Spans, error::{ErrorKind, ScorchError, ScorchResult}, interpreter::Interpreter};
 
 use super::types::Instance;
+use indexmap::IndexMap;
 use std::{fmt::Debug, rc::Rc, cell::RefCell, collections::HashMap};
 
 #[derive(Debug, PartialEq)]
@@ -11,25 +12,69 @@ pub enum Attr {
     Function,
 }
 
+pub type OperatorFn = Rc<dyn Fn(&mut Interpreter, &Value, &Value) -> ScorchResult + 'static>;
+
 pub struct OperatorOverload {
+    // `dynamic` accepts any rhs, an exact match is preferred.
     pub rhs_t: String,
     pub op : TokenKind,
-    pub method : Box<dyn Fn(&Value, &Value) -> Value + 'static>,
+    pub method : OperatorFn,
+}
+
+// functions declared with these names in a `within` block overload the operator.
+// TODO: Modulo, Exponent, the Bitwise and the Shift kinds are assumed to be lexed & parsed by the
+// scorch-parser rev Cargo.lock pins (125a092). that hasn't been built against yet, if they're
+// missing there the parser needs them first, with the pin bumped to that rev.
+pub fn overloaded_operator(name: &str) -> Option<TokenKind> {
+    let op = match name {
+        "add" => TokenKind::Add,
+        "sub" => TokenKind::Subtract,
+        "mul" => TokenKind::Multiply,
+        "div" => TokenKind::Divide,
+        "mod" => TokenKind::Modulo,
+        "pow" => TokenKind::Exponent,
+        "eq" => TokenKind::Equals,
+        "ne" => TokenKind::NotEquals,
+        "lt" => TokenKind::LeftAngle,
+        "le" => TokenKind::LessThanEquals,
+        "gt" => TokenKind::RightAngle,
+        "ge" => TokenKind::GreaterThanEquals,
+        "and" => TokenKind::LogicalAnd,
+        "or" => TokenKind::LogicalOr,
+        "bitand" => TokenKind::BitwiseAnd,
+        "bitor" => TokenKind::BitwiseOr,
+        "bitxor" => TokenKind::BitwiseXor,
+        "shl" => TokenKind::LeftShift,
+        "shr" => TokenKind::RightShift,
+        _ => return None,
+    };
+    Some(op)
 }
 
 
 pub struct Type {
     pub name: String,
-    pub validator: Box<fn(&Value) -> bool>,
+    pub validator: Box<dyn Fn(&Value) -> bool>,
     pub attribute: Attr,
     pub operators: Vec<OperatorOverload>,
-    pub context : Box<Context>
+    pub context : Box<Context>,
+    // a struct's field declarations, evaluated again for every instance so none share fields.
+    pub field_decls: Option<Rc<FieldDecls>>,
+    // the scope a struct was declared in, its field defaults are evaluated there.
+    // held like a function holds its closure, instances of the type are stored in that scope.
+    pub scope: Option<Closure>,
+}
+
+pub struct FieldDecls {
+    pub statements: Vec<Box<Node>>,
+    // where they were written, they outlive the tree they were cloned from.
+    pub spans: Rc<Spans>,
 }
 
 impl Debug for Type {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "Type {{\n  name: {}, \n  attribute: {:?} \n  # of operator overloads: {}\n  # of fields on type : {}\n}}",
-         self.name, self.attribute, self.operators.len(), self.context.variables.len())
+         self.name, self.attribute, self.operators.len(), self.field_decls.as_ref().map_or(0, |decls| decls.statements.len()))
     }
 }
 
@@ -37,20 +82,33 @@ impl Type {
     pub fn validate(&self, val: &Value) -> bool {
         (self.validator)(val)
     }
-    pub fn perform_bin_op<'a>(&'a self, op: &'a TokenKind, rhs_t: &Rc<RefCell<Type>>, lhs_value : &'a Value, other : &'a Value) -> Value {
+    pub fn find_operator(&self, op: &TokenKind, rhs_tname: &str) -> Option<&OperatorOverload> {
+        let find = |rhs_tname: &str| {
+            self.operators
+                .iter()
+                .find(|op_ovr| op_ovr.op == *op && op_ovr.rhs_t == rhs_tname)
+        };
+        find(rhs_tname).or_else(|| find(DYNAMIC_TNAME))
+    }
+    // adds an overload, replacing any previous one for the same operator & rhs type.
+    pub fn add_operator(&mut self, overload: OperatorOverload) {
+        self.operators
+            .retain(|op_ovr| !(op_ovr.op == overload.op && op_ovr.rhs_t == overload.rhs_t));
+        self.operators.push(overload);
+    }
+    // the overload is cloned out, so the type isn't borrowed while it runs.
+    pub fn overload_for(&self, op: &TokenKind, rhs_t: &Rc<RefCell<Type>>) -> ScorchResult<OperatorFn> {
         let other_tname = rhs_t.borrow().name.clone();
         
-        let op_ovr = self.operators.iter().find(|op_ovr| {
-            op_ovr.op == *op && op_ovr.rhs_t == other_tname
-        });
+        let op_ovr = self.find_operator(op, &other_tname);
         
         match &op_ovr {
-            Some(op_ovr) => {
-                let result = (op_ovr.method)(lhs_value, other);
-                return result.clone();
-            },
+            Some(op_ovr) => Ok(Rc::clone(&op_ovr.method)),
             None => {
-                panic!("no operator overload found operator {:?} for type {} and type {}",op, self.name, other_tname);
+                Err(ScorchError::new(
+                    ErrorKind::InvalidOperator,
+                    format!("no operator overload found operator {:?} for type {} and type {}", op, self.name, other_tname),
+                ))
             }
         }
     }
@@ -72,8 +130,10 @@ impl TypeChecker {
                             _ => false,
                         }),
                         attribute: Attr::Value,
-                        context: Box::new(Context { parent: None, variables: HashMap::new() }),
+                        context: Box::new(Context { parent: None, variables: IndexMap::new() }),
                         operators: Vec::new(),
+                        field_decls: None,
+                        scope: None,
                     })),
                 ),
                 (
@@ -85,8 +145,10 @@ impl TypeChecker {
                             _ => false,
                         }),
                         attribute: Attr::Value,
-                        context: Box::new(Context { parent: None, variables: HashMap::new() }),
+                        context: Box::new(Context { parent: None, variables: IndexMap::new() }),
                         operators: Vec::new(),
+                        field_decls: None,
+                        scope: None,
                     })),
                 ),
                 (
@@ -98,8 +160,10 @@ impl TypeChecker {
                             _ => false,
                         }),
                         attribute: Attr::Value,
-                        context: Box::new(Context { parent: None, variables: HashMap::new() }),
+                        context: Box::new(Context { parent: None, variables: IndexMap::new() }),
                         operators: Vec::new(),
+                        field_decls: None,
+                        scope: None,
                     })),
                 ),
                 (
@@ -110,8 +174,10 @@ impl TypeChecker {
                             _ => true, // :D
                         }),
                         attribute: Attr::Value,
-                        context: Box::new(Context { parent: None, variables: HashMap::new() }),
+                        context: Box::new(Context { parent: None, variables: IndexMap::new() }),
                         operators: Vec::new(),
+                        field_decls: None,
+                        scope: None,
                     })),
                 ),
                 (
@@ -123,8 +189,10 @@ impl TypeChecker {
                             _ => false,
                         }),
                         attribute: Attr::Value,
-                        context: Box::new(Context { parent: None, variables: HashMap::new() }),
+                        context: Box::new(Context { parent: None, variables: IndexMap::new() }),
                         operators: Vec::new(),
+                        field_decls: None,
+                        scope: None,
                     })),
                 ),
                 (
@@ -136,8 +204,10 @@ impl TypeChecker {
                             _ => false,
                         }),
                         attribute: Attr::Value,
-                        context: Box::new(Context { parent: None, variables: HashMap::new() }),
+                        context: Box::new(Context { parent: None, variables: IndexMap::new() }),
                         operators: Vec::new(),
+                        field_decls: None,
+                        scope: None,
                     })),
                 ),
                 (
@@ -149,8 +219,10 @@ impl TypeChecker {
                             _ => false,
                         }),
                         attribute: Attr::Array,
-                        context: Box::new(Context { parent: None, variables: HashMap::new() }),
+                        context: Box::new(Context { parent: None, variables: IndexMap::new() }),
                         operators: Vec::new(),
+                        field_decls: None,
+                        scope: None,
                     })),
                 ),
                 (
@@ -162,8 +234,10 @@ impl TypeChecker {
                             _ => false,
                         }),
                         attribute: Attr::Function,
-                        context: Box::new(Context { parent: None, variables: HashMap::new() }),
+                        context: Box::new(Context { parent: None, variables: IndexMap::new() }),
                         operators: Vec::new(),
+                        field_decls: None,
+                        scope: None,
                     })),
                 ),
             ]),
@@ -183,33 +257,28 @@ impl TypeChecker {
     }
     pub fn from_value(&self, val: &Value) -> Option<Rc<RefCell<Type>>> {
         let typename = get_typename(val);
-        let result = self.get(&typename);
-        assert!(result.is_some(), "type not found, {}", typename);
-        Some(result.unwrap())
+        self.get(&typename)
     }
 }
 
 // import constants.
 use scorch_parser::{ast::*, lexer::TokenKind};
 
-pub fn get_typename(arg: &Value) -> &str {
+// references & returns are named after the value they hold.
+pub fn get_typename(arg: &Value) -> String {
     match &arg {
-        Value::Array(..) => ARRAY_TNAME,
-        Value::None() => NONE_TNAME,
-        Value::Int(..) => INT_TNAME,
-        Value::Bool(..) => BOOL_TNAME,
-        Value::String(..) => STRING_TNAME,
-        Value::Double(..) => DOUBLE_TNAME,
-        Value::Return(..) => panic!("cannot get the typename of a return node. if you don't know what this means, something has gone seriously wrong."),
+        Value::Array(..) => ARRAY_TNAME.to_string(),
+        Value::None() => NONE_TNAME.to_string(),
+        Value::Int(..) => INT_TNAME.to_string(),
+        Value::Bool(..) => BOOL_TNAME.to_string(),
+        Value::String(..) => STRING_TNAME.to_string(),
+        Value::Double(..) => DOUBLE_TNAME.to_string(),
+        Value::Return(Some(value)) => get_typename(value),
+        Value::Return(None) => NONE_TNAME.to_string(),
+        Value::Reference(inner) => get_typename(&inner.borrow().value),
         // todo: Fix the lack of type checking for functions,
         // we need a more centralized way of checking types for structs & functions.
-        Value::Function(..) => FN_TNAME,
-        Value::StructInstance {
-            typename,
-            context: _,
-        } => typename,
-        _ => {
-            panic!("cannot find type from value {:?}", arg);
-        }
+        Value::Function(..) => FN_TNAME.to_string(),
+        Value::StructInstance { typename, .. } => typename.clone(),
     }
 }
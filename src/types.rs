@@ -1,12 +1,16 @@
-use super::{context::Context, typechecker::Type};
+use super::{context::Context, error::ScorchResult, span::Spans, typechecker::Type};
 use crate::interpreter::Interpreter;
-use scorch_parser::ast::{Node, Visitor};
-use std::{cell::RefCell, rc::Rc};
+use scorch_parser::ast::{Node, Visitor, NONE_TNAME};
+use std::{
+    cell::RefCell,
+    fmt,
+    rc::{Rc, Weak},
+};
 
 #[derive(Debug, Clone)]
 pub enum Value {
     None(),
-    Int(i32),
+    Int(i64),
     Bool(bool),
     Double(f64),
     String(String),
@@ -14,7 +18,8 @@ pub enum Value {
     Return(Option<Box<Value>>),
     Reference(Rc<RefCell<Instance>>),
     Array(bool, Rc<RefCell<Vec<Rc<RefCell<Instance>>>>>),
-    StructInstance { typename: String, context: Box<Context> },
+    // `fields` are the declared field names in order, the context also holds the associated functions.
+    StructInstance { typename: String, context: Box<Context>, fields: Rc<Vec<String>> },
 }
 
 impl Value {
@@ -25,6 +30,13 @@ impl Value {
         };
         value
     }
+    pub fn as_int(&self) -> Option<&i64> {
+        let value = match self {
+            Value::Int(val) => Some(val),
+            _ => None,
+        };
+        value
+    }
     pub fn as_float(&self) -> Option<&f64> {
         let value = match self {
             Value::Double(val) => Some(val),
@@ -39,6 +51,162 @@ impl Value {
         };
         value
     }
+    // copies the value out of a reference, so it can outlive the variable it came from.
+    pub fn dereference(self) -> Value {
+        match self {
+            Value::Reference(inner) => inner.borrow().value.clone(),
+            _ => self,
+        }
+    }
+    // structural equality, arrays compare element wise and structs field wise.
+    // numbers compare the way `==` does, so 1 equals 1.0.
+    pub fn deep_eq(&self, other: &Value) -> bool {
+        self.eq_nested(other, &mut Vec::new())
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_nested(f, &mut Vec::new(), false)
+    }
+}
+
+impl Value {
+    // `visiting` holds the arrays & struct fields currently being printed,
+    // so a value that contains itself prints `[...]` instead of recursing forever.
+    // strings are quoted when nested inside an array or struct.
+    fn fmt_nested(&self, f: &mut fmt::Formatter<'_>, visiting: &mut Vec<*const ()>, nested: bool) -> fmt::Result {
+        match self {
+            Value::None() => write!(f, "{}", NONE_TNAME),
+            Value::Int(val) => write!(f, "{}", val),
+            Value::Double(val) => write!(f, "{}", val),
+            Value::Bool(val) => write!(f, "{}", val),
+            Value::String(val) => {
+                if nested {
+                    write!(f, "'{}'", val)
+                } else {
+                    write!(f, "{}", val)
+                }
+            }
+            Value::Function(func) => write!(f, "{}", func),
+            Value::Return(Some(val)) => val.fmt_nested(f, visiting, nested),
+            Value::Return(None) => write!(f, "{}", NONE_TNAME),
+            Value::Reference(inner) => inner.borrow().value.fmt_nested(f, visiting, nested),
+            Value::Array(_, elements) => {
+                let ptr = Rc::as_ptr(elements) as *const ();
+                if visiting.contains(&ptr) {
+                    return write!(f, "[...]");
+                }
+                visiting.push(ptr);
+                write!(f, "[")?;
+                for (i, element) in elements.borrow().iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    element.borrow().value.fmt_nested(f, visiting, true)?;
+                }
+                visiting.pop();
+                write!(f, "]")
+            }
+            Value::StructInstance { typename, context, fields } => {
+                let fields: Vec<_> = fields
+                    .iter()
+                    .filter_map(|name| context.variables.get(name).map(|var| (name, var)))
+                    .collect();
+
+                if fields.is_empty() {
+                    return write!(f, "{} {{}}", typename);
+                }
+
+                write!(f, "{} {{ ", typename)?;
+                for (i, (name, var)) in fields.into_iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: ", name)?;
+                    let ptr = Rc::as_ptr(var) as *const ();
+                    if visiting.contains(&ptr) {
+                        write!(f, "...")?;
+                        continue;
+                    }
+                    visiting.push(ptr);
+                    var.borrow().value.fmt_nested(f, visiting, true)?;
+                    visiting.pop();
+                }
+                write!(f, " }}")
+            }
+        }
+    }
+    // `visiting` holds the pairs of arrays & fields currently being compared,
+    // meeting a pair again means both sides recurse the same way, so it's treated as equal.
+    fn eq_nested(&self, other: &Value, visiting: &mut Vec<(*const (), *const ())>) -> bool {
+        match (self, other) {
+            (Value::Reference(inner), _) => inner.borrow().value.eq_nested(other, visiting),
+            (_, Value::Reference(inner)) => self.eq_nested(&inner.borrow().value, visiting),
+            (Value::None(), Value::None()) => true,
+            (Value::Int(lhs), Value::Int(rhs)) => lhs == rhs,
+            (Value::Double(lhs), Value::Double(rhs)) => lhs == rhs,
+            (Value::Int(int), Value::Double(double)) | (Value::Double(double), Value::Int(int)) => {
+                *int as f64 == *double
+            }
+            (Value::Bool(lhs), Value::Bool(rhs)) => lhs == rhs,
+            (Value::String(lhs), Value::String(rhs)) => lhs == rhs,
+            // the same declaration evaluated in the same scope, a released copy is still the same function.
+            (Value::Function(lhs), Value::Function(rhs)) => {
+                Rc::ptr_eq(&lhs.body, &rhs.body) && lhs.closure.as_ptr() == rhs.closure.as_ptr()
+            }
+            (Value::Array(_, lhs), Value::Array(_, rhs)) => {
+                let pair = (Rc::as_ptr(lhs) as *const (), Rc::as_ptr(rhs) as *const ());
+                if Rc::ptr_eq(lhs, rhs) || visiting.contains(&pair) {
+                    return true;
+                }
+                let (lhs, rhs) = (lhs.borrow(), rhs.borrow());
+                if lhs.len() != rhs.len() {
+                    return false;
+                }
+                visiting.push(pair);
+                let equal = lhs
+                    .iter()
+                    .zip(rhs.iter())
+                    .all(|(lhs, rhs)| lhs.borrow().value.eq_nested(&rhs.borrow().value, visiting));
+                visiting.pop();
+                equal
+            }
+            (
+                Value::StructInstance { typename: lhs_typename, context: lhs, fields: lhs_names },
+                Value::StructInstance { typename: rhs_typename, context: rhs, fields: rhs_names },
+            ) => {
+                if lhs_typename != rhs_typename {
+                    return false;
+                }
+                // like printing, only the declared fields are part of the value.
+                let fields = |context: &Context, names: &[String]| {
+                    names
+                        .iter()
+                        .filter_map(|name| context.variables.get(name).map(|var| (name.clone(), Rc::clone(var))))
+                        .collect::<Vec<_>>()
+                };
+                let (lhs_fields, rhs_fields) = (fields(lhs.as_ref(), lhs_names), fields(rhs.as_ref(), rhs_names));
+                if lhs_fields.len() != rhs_fields.len() {
+                    return false;
+                }
+                lhs_fields.iter().all(|(name, lhs_var)| {
+                    let Some((_, rhs_var)) = rhs_fields.iter().find(|(rhs_name, _)| rhs_name == name) else {
+                        return false;
+                    };
+                    let pair = (Rc::as_ptr(lhs_var) as *const (), Rc::as_ptr(rhs_var) as *const ());
+                    if Rc::ptr_eq(lhs_var, rhs_var) || visiting.contains(&pair) {
+                        return true;
+                    }
+                    visiting.push(pair);
+                    let equal = lhs_var.borrow().value.eq_nested(&rhs_var.borrow().value, visiting);
+                    visiting.pop();
+                    equal
+                })
+            }
+            _ => false,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -64,32 +232,90 @@ pub struct Parameter {
     pub name: String,
     pub m_type: Rc<RefCell<Type>>,
 }
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct Function {
     pub name: String,
     pub params: Vec<Parameter>,
-    pub body: Box<Node>,
+    // shared by the copies `Context::release` makes, so they keep the same spans.
+    pub body: Rc<Node>,
+    // where the body was written, it outlives the tree it was cloned from.
+    pub spans: Rc<Spans>,
     pub return_type: Rc<RefCell<Type>>,
     pub mutable: bool,
+    // the scope the function was declared in, its body runs on top of this rather than the caller's scope.
+    pub closure: Closure,
+}
+// a function is usually stored in the scope it captures, so holding that scope strongly would keep
+// both alive forever. functions declared at the top level, which the interpreter owns, and ones left
+// behind in a scope that's been exited hold it weakly.
+#[derive(Clone)]
+pub enum Closure {
+    Strong(Rc<RefCell<Context>>),
+    Weak(Weak<RefCell<Context>>),
+}
+impl Closure {
+    pub fn scope(&self) -> Option<Rc<RefCell<Context>>> {
+        match self {
+            Closure::Strong(scope) => Some(Rc::clone(scope)),
+            Closure::Weak(scope) => scope.upgrade(),
+        }
+    }
+    pub fn as_ptr(&self) -> *const RefCell<Context> {
+        match self {
+            Closure::Strong(scope) => Rc::as_ptr(scope),
+            Closure::Weak(scope) => scope.as_ptr(),
+        }
+    }
+}
+impl fmt::Debug for Function {
+    // the closure can contain the function itself, so only its address is printed.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Function")
+            .field("name", &self.name)
+            .field("params", &self.params)
+            .field("body", &self.body)
+            .field("return_type", &self.return_type)
+            .field("mutable", &self.mutable)
+            .field("closure", &self.closure.as_ptr())
+            .finish()
+    }
+}
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let params: Vec<String> = self
+            .params
+            .iter()
+            .map(|param| format!("{}: {}", param.name, param.m_type.borrow().name))
+            .collect();
+        write!(
+            f,
+            "{}({}) -> {}",
+            self.name,
+            params.join(", "),
+            self.return_type.borrow().name
+        )
+    }
 }
 impl Function {
     // todo: replace manual calls with this in interpreter. can also add more
     // procedure here, like injecting args, context swaps.
-    pub fn call(&mut self, i: &mut dyn Visitor<Value>) -> Value {
+    pub fn call(&mut self, i: &mut dyn Visitor<ScorchResult>) -> ScorchResult {
         return self.body.accept(i);
     }
 }
 pub trait Invokable {
-    fn extract_args(interpeter: &mut Interpreter, arguments: &Option<Vec<Node>>) -> Vec<Value>;
+    fn extract_args(interpeter: &mut Interpreter, arguments: &Option<Vec<Node>>) -> ScorchResult<Vec<Value>>;
 }
 impl Invokable for Function {
-    fn extract_args(interpeter: &mut Interpreter, arguments: &Option<Vec<Node>>) -> Vec<Value> {
+    fn extract_args(interpeter: &mut Interpreter, arguments: &Option<Vec<Node>>) -> ScorchResult<Vec<Value>> {
         let mut args = Vec::new();
-        let args_col = arguments.as_ref().unwrap();
+        let Some(args_col) = arguments.as_ref() else {
+            return Ok(args);
+        };
         for arg in args_col {
-            let value = interpeter.eval_deref(arg);
+            let value = interpeter.eval_deref(arg)?;
             args.push(value);
         }
-        args
+        Ok(args)
     }
 }